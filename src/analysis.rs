@@ -0,0 +1,219 @@
+mod atomic_blocks;
+mod atomicity;
+mod deadlock;
+mod fork_join;
+mod goodlock;
+mod graph;
+mod lock_dependencies;
+mod lock_graph;
+mod locks;
+mod lockset;
+mod races;
+mod requests;
+mod statistics;
+mod sync_preserving;
+mod unwritten_reads;
+mod vector_clock;
+mod wcp;
+mod well_formedness;
+
+use crate::error::AnalyzerError;
+use crate::parser::Event;
+use serde::Serialize;
+
+pub use atomic_blocks::AtomicBlocks;
+pub use atomicity::{Atomicity, AtomicityViolation, Transaction};
+pub use deadlock::{Deadlock, DeadlockKind, LockAcquisition};
+pub use fork_join::ForkJoin;
+pub use goodlock::Goodlock;
+pub use lock_dependencies::LockDependencies;
+pub use lock_graph::LockGraph;
+pub use lockset::{Lockset, LocksetViolation};
+pub use races::{Access, HappensBefore, Race};
+pub use requests::Requests;
+pub use statistics::{Statistics, TraceMetadata};
+pub use unwritten_reads::UnwrittenReads;
+pub use wcp::WeakCausallyPrecedes;
+pub use well_formedness::WellFormedness;
+
+/// The results of one or more analysis passes
+#[derive(Debug, Default, Serialize)]
+pub struct Report {
+    /// general information about the trace
+    pub metadata: Option<TraceMetadata>,
+    /// violations of the well-formedness of a trace
+    pub violations: Vec<AnalyzerError>,
+    /// the lock graph of a trace in GraphViz syntax
+    #[serde(skip)]
+    pub lock_graph: Option<String>,
+    /// the dependencies between the threads of a trace in GraphViz syntax
+    #[serde(skip)]
+    pub thread_graph: Option<String>,
+    /// cyclic chains of lock acquisitions which may deadlock
+    pub deadlocks: Vec<Deadlock>,
+    /// pairs of conflicting accesses which are not ordered
+    pub races: Vec<Race>,
+    /// memory locations which are not consistently protected by a lock
+    pub lockset_violations: Vec<LocksetViolation>,
+    /// atomic blocks which are not serializable
+    pub atomicity_violations: Vec<AtomicityViolation>,
+}
+
+impl Report {
+    /// Merges the results of another report into this one
+    ///
+    /// # Arguments
+    ///
+    /// * `other`: the report to merge
+    ///
+    /// returns: () unit
+    ///
+    pub fn merge(&mut self, other: Report) {
+        self.metadata = self.metadata.take().or(other.metadata);
+        self.violations.extend(other.violations);
+        self.lock_graph = self.lock_graph.take().or(other.lock_graph);
+        self.thread_graph = self.thread_graph.take().or(other.thread_graph);
+
+        // the same cycle may be found by several deadlock analyses, only the most certain classification is kept
+        for deadlock in other.deadlocks {
+            match self
+                .deadlocks
+                .iter_mut()
+                .find(|known| known.is_same_cycle(&deadlock))
+            {
+                Some(known) if deadlock.kind == DeadlockKind::Predictable => *known = deadlock,
+                Some(_) => {}
+                None => self.deadlocks.push(deadlock),
+            }
+        }
+
+        self.races.extend(other.races);
+        self.lockset_violations.extend(other.lockset_violations);
+        self.atomicity_violations.extend(other.atomicity_violations);
+    }
+
+    /// Serializes the report into a single JSON document
+    ///
+    /// returns: String the metadata of the trace, all violations and the results of all analyses
+    ///
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("a report only contains serializable values")
+    }
+}
+
+/// A pass over the events of a trace
+pub trait Analysis {
+    /// Inspects a single event of a trace
+    ///
+    /// # Arguments
+    ///
+    /// * `row`: the row of the event in the trace, starting at 1
+    /// * `event`: the to be analyzed event
+    ///
+    /// returns: () unit
+    ///
+    fn on_event(&mut self, row: usize, event: &Event);
+
+    /// Concludes the analysis after the last event of a trace
+    ///
+    /// returns: Report the results of the analysis
+    ///
+    fn finish(self: Box<Self>) -> Report;
+}
+
+/// Runs a set of analysis passes in a single streaming pass over the events of a trace
+///
+/// # Arguments
+///
+/// * `events`: the events of a trace, e.g. a `TraceReader`
+/// * `analyses`: the passes to run
+///
+/// returns: Report the merged results of all passes, every event which could not be read is reported as violation and
+/// skipped, the events are expected to end after an error which prevents reading the remaining trace
+///
+pub fn run_analyses<I>(events: I, mut analyses: Vec<Box<dyn Analysis>>) -> Report
+where
+    I: IntoIterator<Item = Result<Event, AnalyzerError>>,
+{
+    let mut report = Report::default();
+
+    for (row, event) in (1..).zip(events) {
+        let event = match event {
+            Ok(event) => event,
+            Err(err) => {
+                report.violations.push(err);
+                continue;
+            }
+        };
+
+        for analysis in analyses.iter_mut() {
+            analysis.on_event(row, &event);
+        }
+    }
+
+    for analysis in analyses {
+        report.merge(analysis.finish());
+    }
+
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::{Operand, Operation};
+
+    #[derive(Default)]
+    struct EventCounter {
+        rows: Vec<usize>,
+    }
+
+    impl Analysis for EventCounter {
+        fn on_event(&mut self, row: usize, _event: &Event) {
+            self.rows.push(row);
+        }
+
+        fn finish(self: Box<Self>) -> Report {
+            assert_eq!(self.rows, vec![1, 2]);
+
+            Report::default()
+        }
+    }
+
+    #[test]
+    fn succeed_when_running_multiple_analyses() -> Result<(), AnalyzerError> {
+        // arrange
+        let events = vec![
+            Ok(Event {
+                thread_identifier: 1,
+                operation: Operation::Release,
+                operand: Operand::LockIdentifier(2),
+                loc: 3,
+            }),
+            Ok(Event {
+                thread_identifier: 1,
+                operation: Operation::Write,
+                operand: Operand::MemoryLocation(4),
+                loc: 5,
+            }),
+        ];
+
+        // act
+        let report = run_analyses(
+            events,
+            vec![
+                Box::new(EventCounter::default()),
+                Box::new(WellFormedness::default()),
+            ],
+        );
+
+        // assert
+        assert_eq!(report.violations.len(), 1);
+        assert!(matches!(
+            report.violations[0],
+            AnalyzerError::ReleasedNonAcquiredLock { row: 1, .. }
+        ));
+
+        Ok(())
+    }
+}
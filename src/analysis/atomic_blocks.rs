@@ -0,0 +1,59 @@
+use crate::analysis::{Analysis, Report};
+use crate::error::AnalyzerError;
+use crate::parser::{Event, Operation};
+use std::collections::HashMap;
+
+/// Checks that the atomic blocks of every thread are properly nested
+#[derive(Default)]
+pub struct AtomicBlocks {
+    // the rows of the currently open blocks of every thread
+    open_blocks: HashMap<i64, Vec<usize>>,
+    violations: Vec<AnalyzerError>,
+}
+
+impl Analysis for AtomicBlocks {
+    fn on_event(&mut self, row: usize, event: &Event) {
+        match event.operation {
+            Operation::Begin => {
+                self.open_blocks
+                    .entry(event.thread_identifier)
+                    .or_default()
+                    .push(row);
+            }
+            Operation::End => {
+                let closed = self
+                    .open_blocks
+                    .get_mut(&event.thread_identifier)
+                    .and_then(|blocks| blocks.pop());
+
+                if closed.is_none() {
+                    self.violations.push(AnalyzerError::UnmatchedEnd {
+                        row,
+                        thread_id: event.thread_identifier,
+                    });
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn finish(self: Box<Self>) -> Report {
+        let mut violations = self.violations;
+        let mut unclosed = self
+            .open_blocks
+            .into_iter()
+            .flat_map(|(thread_id, rows)| rows.into_iter().map(move |row| (thread_id, row)))
+            .collect::<Vec<_>>();
+
+        unclosed.sort_by_key(|(_, row)| *row);
+
+        for (thread_id, row) in unclosed {
+            violations.push(AnalyzerError::UnclosedBegin { row, thread_id });
+        }
+
+        Report {
+            violations,
+            ..Report::default()
+        }
+    }
+}
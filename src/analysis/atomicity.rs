@@ -0,0 +1,572 @@
+use crate::analysis::graph::{find_cycle, Graph};
+use crate::analysis::{Analysis, Report};
+use crate::parser::{Event, Operation};
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::fmt::{Display, Formatter};
+
+/// A transaction of a thread, either an atomic block or a single event outside of a block
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct Transaction {
+    pub thread_id: i64,
+    pub begin: usize,
+    /// the row where the transaction ends, None if it is still open at the end of the trace
+    pub end: Option<usize>,
+}
+
+impl Display for Transaction {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self.end {
+            Some(end) => write!(
+                f,
+                "thread 'T{}' from row {} to row {}",
+                self.thread_id, self.begin, end
+            ),
+            None => write!(
+                f,
+                "thread 'T{}' from row {} to the end of the trace",
+                self.thread_id, self.begin
+            ),
+        }
+    }
+}
+
+/// Transactions whose conflicting events form a cycle, hence they cannot be serialized
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct AtomicityViolation {
+    pub transactions: Vec<Transaction>,
+}
+
+impl Display for AtomicityViolation {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let transactions = self
+            .transactions
+            .iter()
+            .map(|transaction| transaction.to_string())
+            .collect::<Vec<_>>();
+
+        write!(
+            f,
+            "Atomic blocks are not serializable: {}",
+            transactions.join(" -> ")
+        )
+    }
+}
+
+/// Detects atomicity violations via a transactional happens-before graph (Velodrome)
+#[derive(Default)]
+pub struct Atomicity {
+    graph: Graph,
+    // the transactions which were not collected yet, identified by their index
+    transactions: HashMap<i64, Transaction>,
+    // the node every transaction of a contracted cycle belongs to, and the transactions of every such node
+    representatives: HashMap<i64, i64>,
+    members: HashMap<i64, Vec<i64>>,
+    // the number of incoming edges of every node
+    incoming: HashMap<i64, usize>,
+    next_node: i64,
+    // the open atomic block and its nesting depth of every thread
+    open_blocks: HashMap<i64, (i64, usize)>,
+    last_transaction: HashMap<i64, i64>,
+    last_write: HashMap<i64, i64>,
+    last_reads: HashMap<i64, HashMap<i64, i64>>,
+    last_release: HashMap<i64, i64>,
+    forked_by: HashMap<i64, i64>,
+    cycles: Vec<Vec<i64>>,
+    // the nodes of all reported cycles, these are kept as retired transactions when collected
+    cyclic: HashSet<i64>,
+    retired: HashMap<i64, Transaction>,
+}
+
+impl Atomicity {
+    /// Returns the node of the graph a transaction belongs to
+    fn find(&self, transaction: i64) -> i64 {
+        self.representatives
+            .get(&transaction)
+            .copied()
+            .unwrap_or(transaction)
+    }
+
+    /// Adds a new transaction to the graph which is ordered after the previous transaction of its thread
+    ///
+    /// # Arguments
+    ///
+    /// * `thread_id`: the id of the thread executing the transaction
+    /// * `row`: the row of the first event of the transaction
+    ///
+    /// returns: i64 the node of the transaction
+    ///
+    fn start_transaction(&mut self, thread_id: i64, row: usize) -> i64 {
+        let node = self.next_node;
+        self.next_node += 1;
+
+        self.transactions.insert(
+            node,
+            Transaction {
+                thread_id,
+                begin: row,
+                end: None,
+            },
+        );
+
+        // a new node has no outgoing edges, hence it can't close a cycle
+        if let Some(previous) = self.last_transaction.insert(thread_id, node) {
+            self.add_edge(previous, node);
+        }
+
+        if let Some(parent) = self.forked_by.remove(&thread_id) {
+            self.add_edge(parent, node);
+        }
+
+        node
+    }
+
+    /// Ends a transaction and collects it if no other transaction precedes it
+    ///
+    /// # Arguments
+    ///
+    /// * `node`: the node of the transaction
+    /// * `row`: the row of the last event of the transaction
+    ///
+    /// returns: () unit
+    ///
+    fn end_transaction(&mut self, node: i64, row: usize) {
+        if let Some(transaction) = self.transactions.get_mut(&node) {
+            transaction.end = Some(row);
+        }
+
+        self.collect(self.find(node));
+    }
+
+    /// Adds an edge between two transactions which can't close a cycle
+    ///
+    /// # Arguments
+    ///
+    /// * `from`: the node of the earlier transaction
+    /// * `to`: the node of the later transaction
+    ///
+    /// returns: () unit
+    ///
+    fn add_edge(&mut self, from: i64, to: i64) {
+        // collected transactions have no incoming edges, hence they are never part of a cycle
+        if !self.transactions.contains_key(&from) {
+            return;
+        }
+
+        let (from, to) = (self.find(from), self.find(to));
+
+        if from != to && self.graph.entry(from).or_default().insert(to) {
+            *self.incoming.entry(to).or_default() += 1;
+        }
+    }
+
+    /// Orders two transactions, a cycle closed by the edge is reported and contracted into a single node
+    ///
+    /// # Arguments
+    ///
+    /// * `from`: the node of the earlier transaction
+    /// * `to`: the node of the current transaction
+    ///
+    /// returns: () unit
+    ///
+    fn add_dependency(&mut self, from: Option<i64>, to: i64) {
+        let (from, to) = match from {
+            Some(from) if self.transactions.contains_key(&from) => (self.find(from), self.find(to)),
+            _ => return,
+        };
+
+        if from == to {
+            return;
+        }
+
+        let has_successors = self
+            .graph
+            .get(&to)
+            .is_some_and(|children| !children.is_empty());
+
+        if !self.graph.entry(from).or_default().insert(to) {
+            return;
+        }
+
+        // the graph is acyclic, therefore every new cycle contains the added edge
+        if has_successors {
+            if let Some(cycle) = find_cycle(&self.graph, to, &mut HashSet::new()) {
+                self.contract(&cycle);
+                self.cyclic.extend(&cycle);
+                self.cycles.push(cycle);
+
+                return;
+            }
+        }
+
+        *self.incoming.entry(to).or_default() += 1;
+    }
+
+    /// Merges the nodes of a cycle into its first node, which keeps the graph acyclic without dropping any edge,
+    /// hence a contracted cycle is never reported twice
+    ///
+    /// # Arguments
+    ///
+    /// * `cycle`: the nodes of the cycle in the order of its edges
+    ///
+    /// returns: () unit
+    ///
+    fn contract(&mut self, cycle: &[i64]) {
+        let representative = cycle[0];
+        let merged = cycle[1..].iter().copied().collect::<HashSet<_>>();
+
+        for node in &merged {
+            let members = self.members.remove(node).unwrap_or_else(|| vec![*node]);
+
+            for member in &members {
+                self.representatives.insert(*member, representative);
+            }
+
+            self.members
+                .entry(representative)
+                .or_insert_with(|| vec![representative])
+                .extend(members);
+
+            let children = self.graph.remove(node).unwrap_or_default();
+            self.graph
+                .entry(representative)
+                .or_default()
+                .extend(children);
+        }
+
+        for children in self.graph.values_mut() {
+            if children.iter().any(|child| merged.contains(child)) {
+                children.retain(|child| !merged.contains(child));
+                children.insert(representative);
+            }
+        }
+
+        self.graph
+            .entry(representative)
+            .or_default()
+            .remove(&representative);
+
+        // cycles are rare, hence the incoming edges are simply counted again
+        self.incoming.clear();
+
+        for child in self.graph.values().flatten() {
+            *self.incoming.entry(*child).or_default() += 1;
+        }
+    }
+
+    /// Removes a finished transaction without incoming edges from the graph, as well as every finished transaction
+    /// which loses its last incoming edge by it, since no later edge can lead to them anymore
+    ///
+    /// # Arguments
+    ///
+    /// * `node`: the node of the transaction
+    ///
+    /// returns: () unit
+    ///
+    fn collect(&mut self, node: i64) {
+        let mut collectable = vec![node];
+
+        while let Some(node) = collectable.pop() {
+            let members = self
+                .members
+                .get(&node)
+                .cloned()
+                .unwrap_or_else(|| vec![node]);
+
+            // a contracted cycle is finished once all of its transactions are
+            let is_finished = members.iter().all(|member| {
+                self.transactions
+                    .get(member)
+                    .is_some_and(|transaction| transaction.end.is_some())
+            });
+
+            if !is_finished || self.incoming.get(&node).is_some_and(|count| *count > 0) {
+                continue;
+            }
+
+            self.members.remove(&node);
+            self.incoming.remove(&node);
+
+            for member in members {
+                let transaction = self.transactions.remove(&member).unwrap();
+                self.representatives.remove(&member);
+
+                if self.cyclic.contains(&member) {
+                    self.retired.insert(member, transaction);
+                }
+            }
+
+            for child in self.graph.remove(&node).unwrap_or_default() {
+                if let Some(count) = self.incoming.get_mut(&child) {
+                    *count -= 1;
+
+                    if *count == 0 {
+                        collectable.push(child);
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl Analysis for Atomicity {
+    fn on_event(&mut self, row: usize, event: &Event) {
+        let thread_id = event.thread_identifier;
+
+        match event.operation {
+            Operation::Begin => {
+                match self.open_blocks.get_mut(&thread_id) {
+                    Some((_, depth)) => *depth += 1,
+                    None => {
+                        let node = self.start_transaction(thread_id, row);
+                        self.open_blocks.insert(thread_id, (node, 1));
+                    }
+                }
+
+                return;
+            }
+            Operation::End => {
+                if let Some((node, depth)) = self.open_blocks.get_mut(&thread_id) {
+                    *depth -= 1;
+
+                    // only the outermost block forms a transaction
+                    if *depth == 0 {
+                        let node = *node;
+                        self.open_blocks.remove(&thread_id);
+                        self.end_transaction(node, row);
+                    }
+                }
+
+                return;
+            }
+            _ => {}
+        }
+
+        // an event outside of a block forms a transaction on its own
+        let (node, is_unary) = match self.open_blocks.get(&thread_id) {
+            Some((node, _)) => (*node, false),
+            None => (self.start_transaction(thread_id, row), true),
+        };
+
+        let operand = event.operand.id().unwrap_or_default();
+
+        match event.operation {
+            Operation::Read => {
+                self.add_dependency(self.last_write.get(&operand).copied(), node);
+                self.last_reads
+                    .entry(operand)
+                    .or_default()
+                    .insert(thread_id, node);
+            }
+            Operation::Write => {
+                self.add_dependency(self.last_write.get(&operand).copied(), node);
+
+                for read in self
+                    .last_reads
+                    .remove(&operand)
+                    .unwrap_or_default()
+                    .into_values()
+                {
+                    self.add_dependency(Some(read), node);
+                }
+
+                self.last_write.insert(operand, node);
+            }
+            Operation::Acquire => {
+                self.add_dependency(self.last_release.get(&operand).copied(), node);
+            }
+            Operation::Release => {
+                self.last_release.insert(operand, node);
+            }
+            Operation::Fork => {
+                // the child may have begun its first block before it was forked
+                match self.open_blocks.get(&operand) {
+                    Some((child, _)) => self.add_dependency(Some(node), *child),
+                    None => {
+                        self.forked_by.insert(operand, node);
+                    }
+                }
+            }
+            Operation::Join => {
+                self.add_dependency(self.last_transaction.get(&operand).copied(), node);
+            }
+            _ => {}
+        }
+
+        if is_unary {
+            self.end_transaction(node, row);
+        }
+    }
+
+    fn finish(self: Box<Self>) -> Report {
+        let atomicity_violations = self
+            .cycles
+            .iter()
+            .map(|cycle| AtomicityViolation {
+                transactions: cycle
+                    .iter()
+                    .map(|node| {
+                        self.transactions
+                            .get(node)
+                            .or_else(|| self.retired.get(node))
+                            .cloned()
+                            .unwrap()
+                    })
+                    .collect(),
+            })
+            .collect();
+
+        Report {
+            atomicity_violations,
+            ..Report::default()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::Operand;
+
+    fn event(thread_identifier: i64, operation: Operation, location: i64) -> Event {
+        Event {
+            thread_identifier,
+            operation,
+            operand: Operand::MemoryLocation(location),
+            loc: 0,
+        }
+    }
+
+    #[test]
+    fn succeed_when_collecting_finished_transactions() {
+        // arrange
+        let mut analysis = Atomicity::default();
+        let mut events = Vec::new();
+
+        for _ in 0..100 {
+            events.push(event(1, Operation::Write, 1));
+            events.push(event(2, Operation::Read, 1));
+        }
+
+        events.push(event(1, Operation::Begin, 0));
+        events.push(event(1, Operation::Read, 1));
+        events.push(event(2, Operation::Write, 1));
+        events.push(event(1, Operation::Write, 1));
+        events.push(event(1, Operation::End, 0));
+
+        // act
+        for (row, event) in (1..).zip(events.iter()) {
+            analysis.on_event(row, event);
+        }
+
+        // assert
+        assert!(analysis.transactions.is_empty());
+
+        let report = Box::new(analysis).finish();
+
+        assert_eq!(report.atomicity_violations.len(), 1);
+        assert_eq!(
+            report.atomicity_violations[0].transactions,
+            vec![
+                Transaction {
+                    thread_id: 1,
+                    begin: 201,
+                    end: Some(205),
+                },
+                Transaction {
+                    thread_id: 2,
+                    begin: 203,
+                    end: Some(203),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn detect_cycle_over_fork_after_begin() {
+        // arrange
+        let mut analysis = Atomicity::default();
+        let events = [
+            event(1, Operation::Begin, 0),
+            event(2, Operation::Begin, 0),
+            event(1, Operation::Fork, 2),
+            event(2, Operation::Write, 1),
+            event(1, Operation::Read, 1),
+            event(1, Operation::End, 0),
+            event(2, Operation::End, 0),
+        ];
+
+        // act
+        for (row, event) in (1..).zip(events.iter()) {
+            analysis.on_event(row, event);
+        }
+
+        // assert
+        let report = Box::new(analysis).finish();
+
+        assert_eq!(report.atomicity_violations.len(), 1);
+        assert_eq!(
+            report.atomicity_violations[0].transactions,
+            vec![
+                Transaction {
+                    thread_id: 1,
+                    begin: 1,
+                    end: Some(6),
+                },
+                Transaction {
+                    thread_id: 2,
+                    begin: 2,
+                    end: Some(7),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn detect_cycle_over_conflict_of_reported_cycle() {
+        // arrange
+        let mut analysis = Atomicity::default();
+        let events = [
+            event(1, Operation::Begin, 0),
+            event(3, Operation::Begin, 0),
+            event(3, Operation::Write, 2),
+            event(1, Operation::Read, 1),
+            event(2, Operation::Begin, 0),
+            event(2, Operation::Read, 2),
+            event(2, Operation::Write, 1),
+            event(2, Operation::End, 0),
+            event(1, Operation::Write, 1),
+            event(1, Operation::Write, 3),
+            event(3, Operation::Read, 3),
+            event(1, Operation::End, 0),
+            event(3, Operation::End, 0),
+        ];
+
+        // act
+        for (row, event) in (1..).zip(events.iter()) {
+            analysis.on_event(row, event);
+        }
+
+        // assert
+        assert!(analysis.transactions.is_empty());
+
+        let report = Box::new(analysis).finish();
+
+        assert_eq!(report.atomicity_violations.len(), 2);
+        assert_eq!(
+            report.atomicity_violations[1].transactions,
+            vec![
+                Transaction {
+                    thread_id: 3,
+                    begin: 2,
+                    end: Some(13),
+                },
+                Transaction {
+                    thread_id: 1,
+                    begin: 1,
+                    end: Some(12),
+                },
+            ]
+        );
+    }
+}
@@ -0,0 +1,96 @@
+use serde::Serialize;
+use std::fmt::{Display, Formatter};
+
+/// The acquisition of a lock while other locks are held
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct LockAcquisition {
+    pub thread_id: i64,
+    pub lock_id: i64,
+    pub held_locks: Vec<i64>,
+    pub row: usize,
+}
+
+impl Display for LockAcquisition {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let held_locks = self
+            .held_locks
+            .iter()
+            .map(|lock_id| format!("L{lock_id}"))
+            .collect::<Vec<_>>();
+
+        write!(
+            f,
+            "thread 'T{}' acquires 'L{}' in row {} while holding {{{}}}",
+            self.thread_id,
+            self.lock_id,
+            self.row,
+            held_locks.join(", ")
+        )
+    }
+}
+
+/// How certain it is that a deadlock can be reached by a reordering of the trace
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DeadlockKind {
+    /// the cyclic chain exists, but it was not checked whether a reordering reaches it
+    Potential,
+    /// a sync-preserving reordering of the trace reaches the deadlock
+    Predictable,
+}
+
+/// A cyclic chain of lock acquisitions where every thread waits for a lock held by the next one
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct Deadlock {
+    pub kind: DeadlockKind,
+    pub acquisitions: Vec<LockAcquisition>,
+}
+
+impl Deadlock {
+    /// Checks if two deadlocks form the same cycle of acquisitions, regardless of their rows and where the cycle starts
+    ///
+    /// # Arguments
+    ///
+    /// * `other`: the deadlock to compare with
+    ///
+    /// returns: bool true if both consist of the same acquisitions by the same threads
+    ///
+    pub fn is_same_cycle(&self, other: &Deadlock) -> bool {
+        self.chain() == other.chain()
+    }
+
+    fn chain(&self) -> Vec<(i64, i64, &[i64])> {
+        let mut chain = self
+            .acquisitions
+            .iter()
+            .map(|acquisition| {
+                (
+                    acquisition.thread_id,
+                    acquisition.lock_id,
+                    acquisition.held_locks.as_slice(),
+                )
+            })
+            .collect::<Vec<_>>();
+
+        chain.sort_unstable();
+
+        chain
+    }
+}
+
+impl Display for Deadlock {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let acquisitions = self
+            .acquisitions
+            .iter()
+            .map(|acquisition| acquisition.to_string())
+            .collect::<Vec<_>>();
+
+        let kind = match self.kind {
+            DeadlockKind::Potential => "Potential",
+            DeadlockKind::Predictable => "Predictable",
+        };
+
+        write!(f, "{kind} deadlock: {}", acquisitions.join(" -> "))
+    }
+}
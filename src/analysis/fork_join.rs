@@ -0,0 +1,135 @@
+use crate::analysis::{Analysis, Report};
+use crate::error::AnalyzerError;
+use crate::parser::{Event, Operation};
+use std::collections::HashMap;
+
+#[derive(Default)]
+struct ThreadState {
+    first_event: Option<usize>,
+    forked: Option<usize>,
+    joined: Option<usize>,
+}
+
+/// Checks that threads are forked before they start, joined after they finished and each only once
+#[derive(Default)]
+pub struct ForkJoin {
+    threads: HashMap<i64, ThreadState>,
+    violations: Vec<AnalyzerError>,
+}
+
+impl ForkJoin {
+    /// Checks the fork of a child thread
+    ///
+    /// # Arguments
+    ///
+    /// * `row`: the current row of the trace
+    /// * `thread_id`: the id of the forking thread
+    /// * `child_id`: the id of the forked thread
+    ///
+    /// returns: Result<(), AnalyzerError> unit if the fork is well-formed, an error otherwise
+    ///
+    fn fork(&mut self, row: usize, thread_id: i64, child_id: i64) -> Result<(), AnalyzerError> {
+        if thread_id == child_id {
+            return Err(AnalyzerError::ForkedItself { row, thread_id });
+        }
+
+        let child = self.threads.entry(child_id).or_default();
+
+        if let Some(previous) = child.forked {
+            return Err(AnalyzerError::RepeatedFork {
+                attempted: row,
+                previous,
+                thread_id,
+                child_id,
+            });
+        }
+
+        child.forked = Some(row);
+
+        if let Some(first_event) = child.first_event {
+            return Err(AnalyzerError::ForkedStartedThread {
+                row,
+                thread_id,
+                child_id,
+                first_event,
+            });
+        }
+
+        Ok(())
+    }
+
+    /// Checks the join of a child thread
+    ///
+    /// # Arguments
+    ///
+    /// * `row`: the current row of the trace
+    /// * `thread_id`: the id of the joining thread
+    /// * `child_id`: the id of the joined thread
+    ///
+    /// returns: Result<(), AnalyzerError> unit if the join is well-formed, an error otherwise
+    ///
+    fn join(&mut self, row: usize, thread_id: i64, child_id: i64) -> Result<(), AnalyzerError> {
+        if thread_id == child_id {
+            return Err(AnalyzerError::JoinedItself { row, thread_id });
+        }
+
+        let child = self.threads.entry(child_id).or_default();
+
+        if let Some(previous) = child.joined {
+            return Err(AnalyzerError::RepeatedJoin {
+                attempted: row,
+                previous,
+                thread_id,
+                child_id,
+            });
+        }
+
+        child.joined = Some(row);
+
+        if child.forked.is_none() {
+            return Err(AnalyzerError::JoinedNonForkedThread {
+                row,
+                thread_id,
+                child_id,
+            });
+        }
+
+        Ok(())
+    }
+}
+
+impl Analysis for ForkJoin {
+    fn on_event(&mut self, row: usize, event: &Event) {
+        let thread = self.threads.entry(event.thread_identifier).or_default();
+
+        // RAPID writes the `begin` marking the start of a thread before the fork of the thread
+        if event.operation != Operation::Begin {
+            thread.first_event.get_or_insert(row);
+        }
+
+        if let Some(joined) = thread.joined {
+            self.violations.push(AnalyzerError::EventAfterJoin {
+                row,
+                thread_id: event.thread_identifier,
+                joined,
+            });
+        }
+
+        let result = match event.operation {
+            Operation::Fork => self.fork(row, event.thread_identifier, event.operand.id().unwrap()),
+            Operation::Join => self.join(row, event.thread_identifier, event.operand.id().unwrap()),
+            _ => Ok(()),
+        };
+
+        if let Err(error) = result {
+            self.violations.push(error);
+        }
+    }
+
+    fn finish(self: Box<Self>) -> Report {
+        Report {
+            violations: self.violations,
+            ..Report::default()
+        }
+    }
+}
@@ -0,0 +1,150 @@
+use crate::analysis::deadlock::{Deadlock, DeadlockKind, LockAcquisition};
+use crate::analysis::graph::{add_edge, elementary_cycles, Graph};
+use crate::analysis::locks::LockTable;
+use crate::analysis::{Analysis, Report};
+use crate::arguments::LockSemantics;
+use crate::parser::{Event, Operation};
+use std::collections::HashMap;
+
+// the first row of every acquisition by a thread with a set of held locks
+type Acquisitions = HashMap<(i64, Vec<i64>), usize>;
+
+/// Predicts deadlocks from cycles in the lock-order graph whose threads are distinct and not guarded by a common lock (Goodlock)
+#[derive(Default)]
+pub struct Goodlock {
+    locks: LockTable,
+    // the acquisitions which caused an edge of the lock-order graph, deduplicated by thread and held locks
+    edges: HashMap<(i64, i64), Acquisitions>,
+}
+
+impl Goodlock {
+    pub fn new(lock_semantics: LockSemantics) -> Self {
+        Self {
+            locks: LockTable::new(lock_semantics),
+            ..Self::default()
+        }
+    }
+
+    /// Returns the acquisitions which caused an edge of the lock-order graph ordered by their row
+    fn acquisitions(&self, from: i64, to: i64) -> Vec<LockAcquisition> {
+        let mut acquisitions = self
+            .edges
+            .get(&(from, to))
+            .into_iter()
+            .flatten()
+            .map(|((thread_id, held_locks), row)| LockAcquisition {
+                thread_id: *thread_id,
+                lock_id: to,
+                held_locks: held_locks.clone(),
+                row: *row,
+            })
+            .collect::<Vec<_>>();
+
+        acquisitions.sort_by_key(|acquisition| acquisition.row);
+
+        acquisitions
+    }
+}
+
+impl Analysis for Goodlock {
+    fn on_event(&mut self, row: usize, event: &Event) {
+        match event.operation {
+            Operation::Acquire => {
+                let lock_id = event.operand.id().unwrap();
+                let held_locks = self
+                    .locks
+                    .track_acquire(event.thread_identifier, lock_id, row)
+                    .unwrap_or_default();
+
+                for owned_lock in &held_locks {
+                    self.edges
+                        .entry((*owned_lock, lock_id))
+                        .or_default()
+                        .entry((event.thread_identifier, held_locks.clone()))
+                        .or_insert(row);
+                }
+            }
+            Operation::Release => {
+                let lock_id = event.operand.id().unwrap();
+                self.locks
+                    .track_release(event.thread_identifier, lock_id, row);
+            }
+            _ => {}
+        }
+    }
+
+    fn finish(self: Box<Self>) -> Report {
+        let mut graph = Graph::new();
+
+        for (from, to) in self.edges.keys() {
+            add_edge(&mut graph, *from, *to);
+        }
+
+        let deadlocks = elementary_cycles(&graph)
+            .into_iter()
+            .filter_map(|cycle| {
+                let candidates = cycle
+                    .iter()
+                    .zip(cycle.iter().cycle().skip(1))
+                    .map(|(from, to)| self.acquisitions(*from, *to))
+                    .collect::<Vec<_>>();
+
+                let mut chosen = Vec::new();
+
+                if choose_acquisitions(&candidates, &mut chosen) {
+                    Some(Deadlock {
+                        kind: DeadlockKind::Potential,
+                        acquisitions: chosen,
+                    })
+                } else {
+                    None
+                }
+            })
+            .collect();
+
+        Report {
+            deadlocks,
+            ..Report::default()
+        }
+    }
+}
+
+/// Chooses one acquisition per edge of a cycle such that all threads are distinct and no lock is held twice
+///
+/// # Arguments
+///
+/// * `candidates`: the acquisitions of every edge of the cycle
+/// * `chosen`: the acquisitions chosen for the previous edges
+///
+/// returns: bool true if a valid acquisition was chosen for every edge
+///
+fn choose_acquisitions(
+    candidates: &[Vec<LockAcquisition>],
+    chosen: &mut Vec<LockAcquisition>,
+) -> bool {
+    let Some(acquisitions) = candidates.get(chosen.len()) else {
+        return true;
+    };
+
+    for acquisition in acquisitions {
+        let is_compatible = chosen.iter().all(|other| {
+            other.thread_id != acquisition.thread_id
+                && other
+                    .held_locks
+                    .iter()
+                    .all(|lock_id| !acquisition.held_locks.contains(lock_id))
+        });
+
+        if is_compatible {
+            chosen.push(acquisition.clone());
+
+            if choose_acquisitions(candidates, chosen) {
+                return true;
+            }
+
+            chosen.pop();
+        }
+    }
+
+    false
+}
@@ -0,0 +1,302 @@
+use std::collections::{HashMap, HashSet};
+
+// a directed graph given by the children of every node
+pub(crate) type Graph = HashMap<i64, HashSet<i64>>;
+
+/// Helper function to add an edge to a graph
+///
+/// # Arguments
+///
+/// * `graph`: the current graph structure
+/// * `from`: the origin of an edge
+/// * `to`: the target of an edge
+///
+/// returns: ()
+///
+pub(crate) fn add_edge(graph: &mut Graph, from: i64, to: i64) {
+    graph.entry(from).or_default().insert(to);
+}
+
+/// Searches a cycle which is reachable from a node via depth first search
+///
+/// # Arguments
+///
+/// * `graph`: the graph to investigate
+/// * `node`: the node to start the search at
+/// * `visited`: a HashSet containing the already visited nodes, these are not searched again
+///
+/// returns: Option<Vec<i64, Global>> the nodes of the first found cycle in the order of its edges, None if there is no cycle
+///
+pub(crate) fn find_cycle(graph: &Graph, node: i64, visited: &mut HashSet<i64>) -> Option<Vec<i64>> {
+    // the stack is kept explicitly since the graphs of large traces are too deep for recursion
+    let mut recursion_stack = vec![node];
+    let mut on_stack = HashSet::from([node]);
+    let mut children = vec![graph.get(&node).map(|children| children.iter())];
+
+    visited.insert(node);
+
+    while let Some(unexplored) = children.last_mut() {
+        match unexplored.as_mut().and_then(|unexplored| unexplored.next()) {
+            Some(&child) => {
+                if on_stack.contains(&child) {
+                    let start = recursion_stack.iter().position(|node| *node == child)?;
+
+                    return Some(recursion_stack[start..].to_vec());
+                }
+
+                if visited.insert(child) {
+                    recursion_stack.push(child);
+                    on_stack.insert(child);
+                    children.push(graph.get(&child).map(|children| children.iter()));
+                }
+            }
+            None => {
+                children.pop();
+
+                if let Some(node) = recursion_stack.pop() {
+                    on_stack.remove(&node);
+                }
+            }
+        }
+    }
+
+    None
+}
+
+/// Enumerates all elementary cycles of a graph via Johnson's algorithm
+///
+/// # Arguments
+///
+/// * `graph`: the graph to investigate
+///
+/// returns: Vec<Vec<i64, Global>, Global> every elementary cycle exactly once, starting at its smallest node
+///
+pub(crate) fn elementary_cycles(graph: &Graph) -> Vec<Vec<i64>> {
+    let mut nodes = graph
+        .iter()
+        .flat_map(|(node, children)| std::iter::once(node).chain(children.iter()))
+        .copied()
+        .collect::<Vec<_>>();
+
+    nodes.sort_unstable();
+    nodes.dedup();
+
+    let mut reversed = Graph::new();
+
+    for (node, children) in graph {
+        for child in children {
+            add_edge(&mut reversed, *child, *node);
+        }
+    }
+
+    let mut cycles = Vec::new();
+
+    for start in nodes {
+        // the strongly connected component of the start in the subgraph of all nodes not smaller than the start
+        let forward = reachable(graph, start);
+        let backward = reachable(&reversed, start);
+        let component = forward
+            .intersection(&backward)
+            .copied()
+            .collect::<HashSet<_>>();
+
+        let mut search = CycleSearch {
+            graph,
+            start,
+            component,
+            blocked: HashSet::new(),
+            blocked_by: HashMap::new(),
+            stack: Vec::new(),
+            cycles: &mut cycles,
+        };
+
+        search.circuit(start);
+    }
+
+    cycles
+}
+
+/// Returns all nodes which are reachable from a node via nodes which are not smaller than it
+fn reachable(graph: &Graph, start: i64) -> HashSet<i64> {
+    let mut visited = HashSet::from([start]);
+    let mut stack = vec![start];
+
+    while let Some(node) = stack.pop() {
+        for child in graph.get(&node).into_iter().flatten() {
+            if *child >= start && visited.insert(*child) {
+                stack.push(*child);
+            }
+        }
+    }
+
+    visited
+}
+
+// the state of Johnson's algorithm for a single start node
+struct CycleSearch<'a> {
+    graph: &'a Graph,
+    start: i64,
+    component: HashSet<i64>,
+    blocked: HashSet<i64>,
+    blocked_by: HashMap<i64, HashSet<i64>>,
+    stack: Vec<i64>,
+    cycles: &'a mut Vec<Vec<i64>>,
+}
+
+impl CycleSearch<'_> {
+    fn children(&self, node: i64) -> Vec<i64> {
+        let mut children = self
+            .graph
+            .get(&node)
+            .into_iter()
+            .flatten()
+            .filter(|child| self.component.contains(child))
+            .copied()
+            .collect::<Vec<_>>();
+
+        // a stable order keeps the output deterministic
+        children.sort_unstable();
+
+        children
+    }
+
+    fn circuit(&mut self, start: i64) {
+        // the frames are kept explicitly since the graphs of large traces are too deep for recursion, every frame
+        // holds a node of the stack, its unexplored children and whether a cycle through it was found
+        let mut frames = vec![(start, self.children(start).into_iter(), false)];
+
+        self.stack.push(start);
+        self.blocked.insert(start);
+
+        while let Some((node, unexplored, found)) = frames.last_mut() {
+            match unexplored.next() {
+                Some(child) if child == self.start => {
+                    self.cycles.push(self.stack.clone());
+                    *found = true;
+                }
+                Some(child) => {
+                    if !self.blocked.contains(&child) {
+                        self.stack.push(child);
+                        self.blocked.insert(child);
+                        frames.push((child, self.children(child).into_iter(), false));
+                    }
+                }
+                None => {
+                    let (node, found) = (*node, *found);
+                    frames.pop();
+
+                    if found {
+                        self.unblock(node);
+
+                        if let Some((_, _, parent_found)) = frames.last_mut() {
+                            *parent_found = true;
+                        }
+                    } else {
+                        for child in self.children(node) {
+                            self.blocked_by.entry(child).or_default().insert(node);
+                        }
+                    }
+
+                    self.stack.pop();
+                }
+            }
+        }
+    }
+
+    fn unblock(&mut self, node: i64) {
+        let mut unblocked = vec![node];
+
+        while let Some(node) = unblocked.pop() {
+            self.blocked.remove(&node);
+
+            for blocked in self.blocked_by.remove(&node).unwrap_or_default() {
+                if self.blocked.contains(&blocked) {
+                    unblocked.push(blocked);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn succeed_when_finding_cycle() {
+        // arrange
+        let mut graph = Graph::new();
+        add_edge(&mut graph, 1, 2);
+        add_edge(&mut graph, 2, 3);
+        add_edge(&mut graph, 3, 4);
+        add_edge(&mut graph, 4, 2);
+
+        // act
+        let cycle = find_cycle(&graph, 1, &mut HashSet::new());
+
+        // assert
+        assert_eq!(cycle, Some(vec![2, 3, 4]));
+    }
+
+    #[test]
+    fn succeed_when_graph_is_acyclic() {
+        // arrange
+        let mut graph = Graph::new();
+        add_edge(&mut graph, 1, 2);
+        add_edge(&mut graph, 1, 3);
+        add_edge(&mut graph, 2, 3);
+
+        // act
+        let cycle = find_cycle(&graph, 1, &mut HashSet::new());
+
+        // assert
+        assert_eq!(cycle, None);
+    }
+
+    #[test]
+    fn succeed_when_enumerating_elementary_cycles() {
+        // arrange
+        let mut graph = Graph::new();
+        add_edge(&mut graph, 1, 2);
+        add_edge(&mut graph, 2, 1);
+        add_edge(&mut graph, 2, 3);
+        add_edge(&mut graph, 3, 1);
+        add_edge(&mut graph, 3, 3);
+
+        // act
+        let cycles = elementary_cycles(&graph);
+
+        // assert
+        assert_eq!(cycles, vec![vec![1, 2], vec![1, 2, 3], vec![3]]);
+    }
+
+    #[test]
+    fn succeed_when_searching_circuits_of_deep_graph() {
+        // arrange
+        let mut graph = Graph::new();
+
+        for node in 0..100_000 {
+            add_edge(&mut graph, node, node + 1);
+        }
+
+        add_edge(&mut graph, 100_000, 0);
+
+        let mut cycles = Vec::new();
+        let mut search = CycleSearch {
+            graph: &graph,
+            start: 0,
+            component: (0..=100_000).collect(),
+            blocked: HashSet::new(),
+            blocked_by: HashMap::new(),
+            stack: Vec::new(),
+            cycles: &mut cycles,
+        };
+
+        // act
+        search.circuit(0);
+
+        // assert
+        assert_eq!(cycles.len(), 1);
+        assert_eq!(cycles[0].len(), 100_001);
+    }
+}
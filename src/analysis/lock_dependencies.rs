@@ -0,0 +1,315 @@
+use crate::analysis::deadlock::{Deadlock, DeadlockKind, LockAcquisition};
+use crate::analysis::graph::{add_edge, elementary_cycles, Graph};
+use crate::analysis::locks::LockTable;
+use crate::analysis::sync_preserving::{Instance, SyncPreserving};
+use crate::analysis::{Analysis, Report};
+use crate::arguments::LockSemantics;
+use crate::parser::{Event, Operation};
+use log::{debug, info};
+use std::collections::{HashMap, HashSet};
+use std::fmt::Write;
+
+#[derive(Eq, PartialEq, Hash, Debug, Clone)]
+struct LockDependency {
+    thread_id: i64,
+    lock_id: i64,
+    // sorted and free of duplicates
+    held_locks: Vec<i64>,
+    line: usize,
+}
+
+impl LockDependency {
+    fn is_disjoint(&self, other: &LockDependency) -> bool {
+        self.held_locks
+            .iter()
+            .all(|lock| other.held_locks.binary_search(lock).is_err())
+    }
+}
+
+/// Collects the lock dependencies of a trace and checks them for cyclic chains between threads
+#[derive(Default)]
+pub struct LockDependencies {
+    locks: LockTable,
+    // the index of every recorded dependency by its thread, lock and held locks
+    indices: HashMap<(i64, i64, Vec<i64>), usize>,
+    lock_dependencies: Vec<LockDependency>,
+    // only present if cycles should be checked for a sync-preserving reordering reaching them
+    sync_preserving: Option<SyncPreserving>,
+    // the concrete acquisitions of every dependency
+    instances: Vec<Vec<Instance>>,
+}
+
+impl LockDependencies {
+    pub fn new(lock_semantics: LockSemantics, sync_preserving: bool) -> Self {
+        Self {
+            locks: LockTable::new(lock_semantics),
+            sync_preserving: sync_preserving.then(SyncPreserving::default),
+            ..Self::default()
+        }
+    }
+}
+
+impl Analysis for LockDependencies {
+    fn on_event(&mut self, row: usize, event: &Event) {
+        match event.operation {
+            Operation::Acquire => {
+                let lock_id = event.operand.id().unwrap();
+
+                if let Some(held_locks) =
+                    self.locks
+                        .track_acquire(event.thread_identifier, lock_id, row)
+                {
+                    let key = (event.thread_identifier, lock_id, held_locks.clone());
+                    let next = self.lock_dependencies.len();
+                    let index = *self.indices.entry(key).or_insert(next);
+
+                    if index == next {
+                        self.lock_dependencies.push(LockDependency {
+                            thread_id: event.thread_identifier,
+                            lock_id,
+                            held_locks,
+                            line: row,
+                        });
+                        self.instances.push(Vec::new());
+                    }
+
+                    if let Some(sync_preserving) = &mut self.sync_preserving {
+                        let instance = sync_preserving.instance(row, event.thread_identifier);
+                        self.instances[index].push(instance);
+                    }
+                }
+            }
+            Operation::Release => {
+                let lock_id = event.operand.id().unwrap();
+                self.locks
+                    .track_release(event.thread_identifier, lock_id, row);
+            }
+            _ => {}
+        }
+
+        if let Some(sync_preserving) = &mut self.sync_preserving {
+            sync_preserving.on_event(event);
+        }
+    }
+
+    fn finish(self: Box<Self>) -> Report {
+        let holders = holders_of_locks(&self.lock_dependencies);
+
+        // create graphical representation of the relation between the threads of a trace
+        let mut graphviz_threads = String::new();
+
+        writeln!(&mut graphviz_threads, "digraph G {{").unwrap();
+
+        // clean lock dependencies to rule out false positives (like a cycle where the locks are owned be the identical thread)
+        for entry in &self.lock_dependencies {
+            let children = holders
+                .get(&entry.lock_id)
+                .into_iter()
+                .flatten()
+                .map(|index| &self.lock_dependencies[*index])
+                .filter(|other| other.thread_id != entry.thread_id && other.is_disjoint(entry)) // no guard locks
+                .map(|dependency| dependency.thread_id) // check for chain
+                .collect::<HashSet<_>>();
+
+            // save information in GraphViz syntax
+            let mut children = children.into_iter().collect::<Vec<_>>();
+            children.sort_unstable();
+
+            for child in children {
+                writeln!(
+                    &mut graphviz_threads,
+                    "    T{} -> T{};",
+                    entry.thread_id, child
+                )
+                .unwrap();
+            }
+
+            debug!("{:?}", entry);
+        }
+
+        writeln!(&mut graphviz_threads, "}}").unwrap();
+
+        let deadlocks = dependency_cycles(&self.lock_dependencies, &holders)
+            .into_iter()
+            .map(|cycle| self.deadlock_of(&cycle))
+            .collect::<Vec<_>>();
+
+        info!("{:?} deadlocks were identified", deadlocks.len());
+
+        Report {
+            thread_graph: Some(graphviz_threads),
+            deadlocks,
+            ..Report::default()
+        }
+    }
+}
+
+impl LockDependencies {
+    /// Classifies a cyclic chain of lock dependencies
+    ///
+    /// # Arguments
+    ///
+    /// * `cycle`: the indices of the dependencies of the chain
+    ///
+    /// returns: Deadlock a predictable deadlock with the rows of reachable acquisitions, otherwise a potential one with the rows of the first acquisitions
+    ///
+    fn deadlock_of(&self, cycle: &[usize]) -> Deadlock {
+        let witness = self.sync_preserving.as_ref().and_then(|sync_preserving| {
+            let instances = cycle
+                .iter()
+                .map(|index| self.instances[*index].as_slice())
+                .collect::<Vec<_>>();
+
+            sync_preserving.witness(&instances)
+        });
+
+        let kind = match witness {
+            Some(_) => DeadlockKind::Predictable,
+            None => DeadlockKind::Potential,
+        };
+
+        let acquisitions = cycle
+            .iter()
+            .enumerate()
+            .map(|(position, index)| {
+                let entry = &self.lock_dependencies[*index];
+
+                LockAcquisition {
+                    thread_id: entry.thread_id,
+                    lock_id: entry.lock_id,
+                    held_locks: entry.held_locks.clone(),
+                    row: witness
+                        .as_ref()
+                        .map_or(entry.line, |instances| instances[position].row),
+                }
+            })
+            .collect();
+
+        Deadlock { kind, acquisitions }
+    }
+}
+
+/// Indexes the lock dependencies by the locks they hold
+///
+/// # Arguments
+///
+/// * `lock_dependencies`: a slice containing all lock dependencies of a trace
+///
+/// returns: HashMap<i64, Vec<usize>, RandomState> the indices of all dependencies holding a lock
+///
+fn holders_of_locks(lock_dependencies: &[LockDependency]) -> HashMap<i64, Vec<usize>> {
+    let mut holders = HashMap::<i64, Vec<usize>>::new();
+
+    for (index, dependency) in lock_dependencies.iter().enumerate() {
+        for lock in &dependency.held_locks {
+            holders.entry(*lock).or_default().push(index);
+        }
+    }
+
+    holders
+}
+
+/// Enumerates the cyclic chains of lock dependencies where every thread waits for a lock held by the next one
+///
+/// # Arguments
+///
+/// * `lock_dependencies`: a slice containing all lock dependencies of a trace
+/// * `holders`: the indices of all dependencies holding a lock
+///
+/// returns: Vec<Vec<usize, Global>, Global> the indices of the dependencies of every chain whose threads are distinct and not guarded by a common lock
+///
+fn dependency_cycles(
+    lock_dependencies: &[LockDependency],
+    holders: &HashMap<i64, Vec<usize>>,
+) -> Vec<Vec<usize>> {
+    let mut graph = Graph::new();
+
+    // an edge means that the lock acquired by a dependency is held by another one
+    for (from, entry) in lock_dependencies.iter().enumerate() {
+        for to in holders.get(&entry.lock_id).into_iter().flatten() {
+            let other = &lock_dependencies[*to];
+
+            if other.thread_id != entry.thread_id && other.is_disjoint(entry) {
+                add_edge(&mut graph, from as i64, *to as i64);
+            }
+        }
+    }
+
+    elementary_cycles(&graph)
+        .into_iter()
+        .map(|cycle| {
+            cycle
+                .into_iter()
+                .map(|node| node as usize)
+                .collect::<Vec<_>>()
+        })
+        .filter(|cycle| {
+            // only neighbours are checked by the edges, but all threads and held locks of a chain must be distinct
+            cycle.iter().enumerate().all(|(position, index)| {
+                let entry = &lock_dependencies[*index];
+
+                cycle[position + 1..].iter().all(|other| {
+                    let other = &lock_dependencies[*other];
+
+                    other.thread_id != entry.thread_id && other.is_disjoint(entry)
+                })
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::AnalyzerError;
+    use crate::parser::Operand;
+
+    fn event(thread_identifier: i64, operation: Operation, lock_id: i64) -> Event {
+        Event {
+            thread_identifier,
+            operation,
+            operand: Operand::LockIdentifier(lock_id),
+            loc: 0,
+        }
+    }
+
+    #[test]
+    fn succeed_when_releasing_locks_out_of_order() -> Result<(), AnalyzerError> {
+        // arrange
+        let mut analysis = LockDependencies::default();
+        let events = [
+            event(1, Operation::Acquire, 1),
+            event(1, Operation::Acquire, 2),
+            event(1, Operation::Release, 1),
+            event(1, Operation::Acquire, 3),
+            event(1, Operation::Release, 3),
+            event(1, Operation::Release, 2),
+            event(1, Operation::Acquire, 1),
+            event(1, Operation::Acquire, 2),
+            event(1, Operation::Release, 1),
+            event(1, Operation::Acquire, 3),
+        ];
+
+        // act
+        for (row, event) in (1..).zip(events.iter()) {
+            analysis.on_event(row, event);
+        }
+
+        // assert
+        assert_eq!(
+            analysis
+                .lock_dependencies
+                .iter()
+                .map(|dependency| (dependency.lock_id, dependency.held_locks.clone()))
+                .collect::<Vec<_>>(),
+            vec![(1, vec![]), (2, vec![1]), (3, vec![2])]
+        );
+
+        let mut held_locks = analysis.locks.locks_of_thread(1).collect::<Vec<_>>();
+        held_locks.sort_unstable();
+
+        assert_eq!(held_locks, vec![2, 3]);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,74 @@
+use crate::analysis::locks::LockTable;
+use crate::analysis::{Analysis, Report};
+use crate::arguments::LockSemantics;
+use crate::parser::{Event, Operation};
+use std::collections::HashSet;
+use std::fmt::Write;
+
+// used for the GraphViz representation
+#[derive(Eq, Hash, PartialEq)]
+struct Edge {
+    from: i64,
+    to: i64,
+}
+
+/// Collects the order in which locks are acquired while other locks are owned
+#[derive(Default)]
+pub struct LockGraph {
+    locks: LockTable,
+    edges: HashSet<Edge>,
+}
+
+impl LockGraph {
+    pub fn new(lock_semantics: LockSemantics) -> Self {
+        Self {
+            locks: LockTable::new(lock_semantics),
+            ..Self::default()
+        }
+    }
+}
+
+impl Analysis for LockGraph {
+    fn on_event(&mut self, row: usize, event: &Event) {
+        match event.operation {
+            Operation::Acquire => {
+                let lock_id = event.operand.id().unwrap();
+                let held_locks = self
+                    .locks
+                    .track_acquire(event.thread_identifier, lock_id, row)
+                    .unwrap_or_default();
+
+                for owned_lock in held_locks {
+                    self.edges.insert(Edge {
+                        from: owned_lock,
+                        to: lock_id,
+                    });
+                }
+            }
+            Operation::Release => {
+                let lock_id = event.operand.id().unwrap();
+                self.locks
+                    .track_release(event.thread_identifier, lock_id, row);
+            }
+            _ => {}
+        }
+    }
+
+    fn finish(self: Box<Self>) -> Report {
+        // create graphical representation of the relation between the locks of a trace
+        let mut graphviz_locks = String::new();
+
+        writeln!(&mut graphviz_locks, "digraph G {{").unwrap();
+
+        for entry in self.edges {
+            writeln!(&mut graphviz_locks, "    L{} -> L{};", entry.from, entry.to).unwrap();
+        }
+
+        writeln!(&mut graphviz_locks, "}}").unwrap();
+
+        Report {
+            lock_graph: Some(graphviz_locks),
+            ..Report::default()
+        }
+    }
+}
@@ -0,0 +1,243 @@
+use crate::arguments::LockSemantics;
+use crate::error::AnalyzerError;
+use log::debug;
+use std::collections::{HashMap, HashSet};
+
+struct Lock {
+    owner: Option<i64>,
+    locked: bool,
+    row: usize,
+    // the amount of nested acquisitions by the owner
+    count: usize,
+}
+
+/// Keeps track of the ownership of all locks of a trace
+#[derive(Default)]
+pub(crate) struct LockTable {
+    locks: HashMap<i64, Lock>,
+    // the locks owned by every thread, updated on every acquisition and release
+    held: HashMap<i64, HashSet<i64>>,
+    semantics: LockSemantics,
+}
+
+impl LockTable {
+    pub fn new(semantics: LockSemantics) -> Self {
+        Self {
+            semantics,
+            ..Self::default()
+        }
+    }
+
+    /// Acquires a lock for a thread
+    ///
+    /// # Arguments
+    ///
+    /// * `thread_id`: the id of the acquiring thread
+    /// * `lock_id`: the id of the acquired lock
+    /// * `row`: the current row of the trace
+    ///
+    /// returns: Result<(), AnalyzerError> unit if the acquisition is well-formed, an error otherwise
+    ///
+    pub fn acquire(
+        &mut self,
+        thread_id: i64,
+        lock_id: i64,
+        row: usize,
+    ) -> Result<(), AnalyzerError> {
+        if let Some(lock) = self.locks.get_mut(&lock_id) {
+            if lock.locked && lock.owner.unwrap() != thread_id {
+                return Err(AnalyzerError::RepeatedAcquisition {
+                    lock_id,
+                    thread_id,
+                    owner_id: lock.owner.unwrap(),
+                    row,
+                });
+            }
+
+            if lock.locked {
+                return match self.semantics {
+                    LockSemantics::Reentrant => {
+                        lock.count += 1;
+                        debug!("Thread 'T{thread_id}' re-acquired lock 'L{lock_id}' in line {row}");
+
+                        Ok(())
+                    }
+                    LockSemantics::NonReentrant => Err(AnalyzerError::ReacquiredOwnedLock {
+                        row,
+                        lock_id,
+                        thread_id,
+                        previous: lock.row,
+                    }),
+                };
+            }
+        }
+
+        let lock = Lock {
+            owner: Some(thread_id),
+            locked: true,
+            row,
+            count: 1,
+        };
+
+        self.locks.insert(lock_id, lock);
+        self.held.entry(thread_id).or_default().insert(lock_id);
+        debug!("Thread 'T{thread_id}' acquired lock 'L{lock_id}' in line {row}");
+
+        Ok(())
+    }
+
+    /// Releases a lock of a thread
+    ///
+    /// # Arguments
+    ///
+    /// * `thread_id`: the id of the releasing thread
+    /// * `lock_id`: the id of the released lock
+    /// * `row`: the current row of the trace
+    ///
+    /// returns: Result<(), AnalyzerError> unit if the release is well-formed, an error otherwise
+    ///
+    pub fn release(
+        &mut self,
+        thread_id: i64,
+        lock_id: i64,
+        row: usize,
+    ) -> Result<(), AnalyzerError> {
+        match self.locks.get_mut(&lock_id) {
+            None => {
+                return Err(AnalyzerError::ReleasedNonAcquiredLock {
+                    row,
+                    lock_id,
+                    thread_id,
+                });
+            }
+            Some(lock) => {
+                if !lock.locked {
+                    return Err(AnalyzerError::RepeatedRelease {
+                        attempted: row,
+                        previous: lock.row,
+                        lock_id,
+                        thread_id,
+                    });
+                }
+
+                if let Some(owner) = lock.owner {
+                    if owner != thread_id {
+                        return Err(AnalyzerError::ReleasedNonOwningLock {
+                            row,
+                            lock_id,
+                            thread_id,
+                            owner,
+                        });
+                    }
+                }
+
+                // only the release matching the outermost acquisition frees the lock
+                if lock.count > 1 {
+                    lock.count -= 1;
+                    debug!("Thread 'T{thread_id}' released nested lock 'L{lock_id}' in line {row}");
+
+                    return Ok(());
+                }
+            }
+        }
+
+        let updated_lock = Lock {
+            locked: false,
+            owner: None,
+            row,
+            count: 0,
+        };
+
+        self.locks.insert(lock_id, updated_lock);
+
+        if let Some(held) = self.held.get_mut(&thread_id) {
+            held.remove(&lock_id);
+        }
+
+        debug!("Thread 'T{thread_id}' released lock 'L{lock_id}' in line {row}");
+
+        Ok(())
+    }
+
+    /// Tracks an acquisition for an analysis which leaves the well-formedness of the trace to another pass
+    ///
+    /// # Arguments
+    ///
+    /// * `thread_id`: the id of the acquiring thread
+    /// * `lock_id`: the id of the acquired lock
+    /// * `row`: the current row of the trace
+    ///
+    /// returns: Option<Vec<i64, Global>> the sorted locks held by the thread before, None if it already holds the lock
+    ///
+    pub fn track_acquire(&mut self, thread_id: i64, lock_id: i64, row: usize) -> Option<Vec<i64>> {
+        let mut held_locks = self.locks_of_thread(thread_id).collect::<Vec<_>>();
+        held_locks.sort_unstable();
+
+        // violations are reported by the well-formedness pass
+        let _ = self.acquire(thread_id, lock_id, row);
+
+        // re-acquisitions of reentrant locks can't deadlock, hence they don't order any locks
+        held_locks
+            .binary_search(&lock_id)
+            .is_err()
+            .then_some(held_locks)
+    }
+
+    /// Tracks a release for an analysis which leaves the well-formedness of the trace to another pass
+    ///
+    /// # Arguments
+    ///
+    /// * `thread_id`: the id of the releasing thread
+    /// * `lock_id`: the id of the released lock
+    /// * `row`: the current row of the trace
+    ///
+    /// returns: () unit
+    ///
+    pub fn track_release(&mut self, thread_id: i64, lock_id: i64, row: usize) {
+        let _ = self.release(thread_id, lock_id, row);
+    }
+
+    /// Returns all owned locks of a given thread
+    ///
+    /// # Arguments
+    ///
+    /// * `thread_id`: the id of the to be searched thread
+    ///
+    /// returns: impl Iterator<Item=i64> the ids of the locks the thread owns
+    ///
+    pub fn locks_of_thread(&self, thread_id: i64) -> impl Iterator<Item = i64> + '_ {
+        self.held.get(&thread_id).into_iter().flatten().copied()
+    }
+
+    /// Checks if a thread owns a lock
+    ///
+    /// # Arguments
+    ///
+    /// * `thread_id`: the id of the thread
+    /// * `lock_id`: the id of the lock
+    ///
+    /// returns: bool true if the lock is held by the thread
+    ///
+    pub fn holds(&self, thread_id: i64, lock_id: i64) -> bool {
+        self.locks
+            .get(&lock_id)
+            .is_some_and(|lock| lock.owner == Some(thread_id))
+    }
+
+    /// Returns all locks which are currently held
+    ///
+    /// returns: Vec<(i64, i64, usize), Global> the id, owner and acquisition row of every held lock ordered by the acquisition row
+    ///
+    pub fn held_locks(&self) -> Vec<(i64, i64, usize)> {
+        let mut held_locks = self
+            .locks
+            .iter()
+            .filter(|(_, lock)| lock.locked)
+            .map(|(id, lock)| (*id, lock.owner.unwrap(), lock.row))
+            .collect::<Vec<_>>();
+
+        held_locks.sort_by_key(|(_, _, row)| *row);
+
+        held_locks
+    }
+}
@@ -0,0 +1,153 @@
+use crate::analysis::locks::LockTable;
+use crate::analysis::races::Access;
+use crate::analysis::{Analysis, Report};
+use crate::arguments::LockSemantics;
+use crate::parser::{Event, Operation};
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::fmt::{Display, Formatter};
+
+/// An access after which no common lock protects a shared and modified memory location anymore
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct LocksetViolation {
+    pub location: i64,
+    pub access: Access,
+}
+
+impl Display for LocksetViolation {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Lockset of the shared and modified location 'V{}' became empty at {}",
+            self.location, self.access
+        )
+    }
+}
+
+enum State {
+    Exclusive(i64),
+    Shared,
+    SharedModified,
+}
+
+struct Location {
+    state: State,
+    candidates: HashSet<i64>,
+    reported: bool,
+}
+
+/// Eraser-style analysis which refines the set of locks held on every access of a memory location
+#[derive(Default)]
+pub struct Lockset {
+    locks: LockTable,
+    locations: HashMap<i64, Location>,
+    violations: Vec<LocksetViolation>,
+}
+
+impl Lockset {
+    pub fn new(lock_semantics: LockSemantics) -> Self {
+        Self {
+            locks: LockTable::new(lock_semantics),
+            ..Self::default()
+        }
+    }
+
+    /// Refines the candidate lockset of an accessed memory location
+    ///
+    /// # Arguments
+    ///
+    /// * `location`: the accessed memory location
+    /// * `access`: the access of the memory location
+    ///
+    /// returns: () unit
+    ///
+    fn on_access(&mut self, location: i64, access: Access) {
+        let locks = &self.locks;
+        let thread_id = access.thread_id;
+        let is_write = access.operation == Operation::Write;
+
+        let entry = match self.locations.get_mut(&location) {
+            Some(entry) => entry,
+            None => {
+                self.locations.insert(
+                    location,
+                    Location {
+                        state: State::Exclusive(thread_id),
+                        candidates: locks.locks_of_thread(thread_id).collect(),
+                        reported: false,
+                    },
+                );
+
+                return;
+            }
+        };
+
+        match entry.state {
+            State::Exclusive(owner) if owner == thread_id => return,
+            State::Exclusive(_) => {
+                // the first access of another thread starts the refinement
+                entry.candidates = locks.locks_of_thread(thread_id).collect();
+                entry.state = if is_write {
+                    State::SharedModified
+                } else {
+                    State::Shared
+                };
+            }
+            State::Shared => {
+                entry
+                    .candidates
+                    .retain(|lock| locks.holds(thread_id, *lock));
+
+                if is_write {
+                    entry.state = State::SharedModified;
+                }
+            }
+            State::SharedModified => {
+                entry
+                    .candidates
+                    .retain(|lock| locks.holds(thread_id, *lock));
+            }
+        }
+
+        if matches!(entry.state, State::SharedModified)
+            && entry.candidates.is_empty()
+            && !entry.reported
+        {
+            entry.reported = true;
+            self.violations.push(LocksetViolation { location, access });
+        }
+    }
+}
+
+impl Analysis for Lockset {
+    fn on_event(&mut self, row: usize, event: &Event) {
+        match event.operation {
+            Operation::Read | Operation::Write => {
+                let access = Access {
+                    row,
+                    thread_id: event.thread_identifier,
+                    operation: event.operation.clone(),
+                    loc: event.loc,
+                };
+
+                self.on_access(event.operand.id().unwrap(), access);
+            }
+            Operation::Acquire => {
+                self.locks
+                    .track_acquire(event.thread_identifier, event.operand.id().unwrap(), row);
+            }
+            Operation::Release => {
+                self.locks
+                    .track_release(event.thread_identifier, event.operand.id().unwrap(), row);
+            }
+            _ => {}
+        }
+    }
+
+    fn finish(self: Box<Self>) -> Report {
+        Report {
+            lockset_violations: self.violations,
+            ..Report::default()
+        }
+    }
+}
@@ -0,0 +1,248 @@
+use crate::analysis::vector_clock::{ThreadIndex, VectorClock};
+use crate::analysis::{Analysis, Report};
+use crate::parser::{Event, Operation};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+
+/// A single access of a memory location
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct Access {
+    pub row: usize,
+    pub thread_id: i64,
+    pub operation: Operation,
+    pub loc: i64,
+}
+
+impl Display for Access {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} of thread 'T{}' in row {} (LoC {})",
+            self.operation, self.thread_id, self.row, self.loc
+        )
+    }
+}
+
+/// Two conflicting accesses of a memory location which are not ordered
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct Race {
+    pub location: i64,
+    pub earlier: Access,
+    pub later: Access,
+}
+
+impl Display for Race {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Data race on 'V{}' between {} and {}",
+            self.location, self.earlier, self.later
+        )
+    }
+}
+
+struct Epoch {
+    thread: usize,
+    clock: u32,
+    access: Access,
+}
+
+impl Epoch {
+    fn is_before(&self, clock: &VectorClock) -> bool {
+        self.clock <= clock.get(self.thread)
+    }
+}
+
+#[derive(Default)]
+struct Variable {
+    write: Option<Epoch>,
+    reads: Vec<Epoch>,
+}
+
+/// Remembers the last accesses of every memory location to check new accesses for races (Djit+/FastTrack)
+#[derive(Default)]
+pub(crate) struct AccessHistory {
+    variables: HashMap<i64, Variable>,
+    races: Vec<Race>,
+}
+
+impl AccessHistory {
+    /// Checks a read against the last write of a memory location
+    ///
+    /// # Arguments
+    ///
+    /// * `location`: the read memory location
+    /// * `thread`: the index of the reading thread
+    /// * `clock`: the current clock of the reading thread
+    /// * `access`: the read access
+    ///
+    /// returns: () unit
+    ///
+    pub fn read(&mut self, location: i64, thread: usize, clock: &VectorClock, access: Access) {
+        let variable = self.variables.entry(location).or_default();
+
+        if let Some(write) = &variable.write {
+            if write.thread != thread && !write.is_before(clock) {
+                self.races.push(Race {
+                    location,
+                    earlier: write.access.clone(),
+                    later: access.clone(),
+                });
+            }
+        }
+
+        let epoch = Epoch {
+            thread,
+            clock: clock.get(thread),
+            access,
+        };
+
+        match variable.reads.iter_mut().find(|read| read.thread == thread) {
+            Some(read) => *read = epoch,
+            None => variable.reads.push(epoch),
+        }
+    }
+
+    /// Checks a write against the last write and all reads since then of a memory location
+    ///
+    /// # Arguments
+    ///
+    /// * `location`: the written memory location
+    /// * `thread`: the index of the writing thread
+    /// * `clock`: the current clock of the writing thread
+    /// * `access`: the write access
+    ///
+    /// returns: () unit
+    ///
+    pub fn write(&mut self, location: i64, thread: usize, clock: &VectorClock, access: Access) {
+        let variable = self.variables.entry(location).or_default();
+
+        for previous in variable.write.iter().chain(variable.reads.iter()) {
+            if previous.thread != thread && !previous.is_before(clock) {
+                self.races.push(Race {
+                    location,
+                    earlier: previous.access.clone(),
+                    later: access.clone(),
+                });
+            }
+        }
+
+        variable.reads.clear();
+        variable.write = Some(Epoch {
+            thread,
+            clock: clock.get(thread),
+            access,
+        });
+    }
+
+    /// Returns all races found so far
+    pub fn into_races(self) -> Vec<Race> {
+        self.races
+    }
+}
+
+/// Detects data races via the happens-before relation using vector clocks
+#[derive(Default)]
+pub struct HappensBefore {
+    threads: ThreadIndex,
+    clocks: Vec<VectorClock>,
+    locks: HashMap<i64, VectorClock>,
+    // the clock of the last write of every memory location, only present for schedulable happens-before
+    writes: Option<HashMap<i64, VectorClock>>,
+    history: AccessHistory,
+}
+
+impl HappensBefore {
+    /// Creates a pass which additionally orders every read after the write it reads from (SHB)
+    pub fn schedulable() -> Self {
+        Self {
+            writes: Some(HashMap::new()),
+            ..Self::default()
+        }
+    }
+
+    /// Returns the index of a thread and makes sure it has a clock
+    fn thread(&mut self, thread_id: i64) -> usize {
+        let thread = self.threads.index(thread_id);
+
+        while self.clocks.len() <= thread {
+            self.clocks.push(VectorClock::new(self.clocks.len()));
+        }
+
+        thread
+    }
+}
+
+impl Analysis for HappensBefore {
+    fn on_event(&mut self, row: usize, event: &Event) {
+        let thread = self.thread(event.thread_identifier);
+        let operand = event.operand.id();
+
+        match event.operation {
+            Operation::Read | Operation::Write => {
+                let location = operand.unwrap();
+                let access = Access {
+                    row,
+                    thread_id: event.thread_identifier,
+                    operation: event.operation.clone(),
+                    loc: event.loc,
+                };
+
+                if event.operation == Operation::Read {
+                    self.history
+                        .read(location, thread, &self.clocks[thread], access);
+
+                    // the read can only be scheduled after the write it reads from
+                    if let Some(write) = self
+                        .writes
+                        .as_ref()
+                        .and_then(|writes| writes.get(&location))
+                    {
+                        self.clocks[thread].join(write);
+                    }
+                } else {
+                    self.history
+                        .write(location, thread, &self.clocks[thread], access);
+
+                    if let Some(writes) = &mut self.writes {
+                        writes.insert(location, self.clocks[thread].clone());
+                        self.clocks[thread].increment(thread);
+                    }
+                }
+            }
+            Operation::Acquire => {
+                if let Some(lock) = self.locks.get(&operand.unwrap()) {
+                    self.clocks[thread].join(lock);
+                }
+            }
+            Operation::Release => {
+                self.locks
+                    .insert(operand.unwrap(), self.clocks[thread].clone());
+                self.clocks[thread].increment(thread);
+            }
+            Operation::Fork => {
+                let child = self.thread(operand.unwrap());
+                let parent_clock = self.clocks[thread].clone();
+
+                self.clocks[child].join(&parent_clock);
+                self.clocks[thread].increment(thread);
+            }
+            Operation::Join => {
+                let child = self.thread(operand.unwrap());
+                let child_clock = self.clocks[child].clone();
+
+                self.clocks[thread].join(&child_clock);
+                self.clocks[child].increment(child);
+            }
+            _ => {}
+        }
+    }
+
+    fn finish(self: Box<Self>) -> Report {
+        Report {
+            races: self.history.into_races(),
+            ..Report::default()
+        }
+    }
+}
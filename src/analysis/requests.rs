@@ -0,0 +1,88 @@
+use crate::analysis::{Analysis, Report};
+use crate::error::AnalyzerError;
+use crate::parser::{Event, Operation};
+use std::collections::HashMap;
+
+/// Checks that every acquisition is immediately preceded by a request of the same lock in its thread
+#[derive(Default)]
+pub struct Requests {
+    // the request of a thread if it is the last event of the thread
+    pending: HashMap<i64, (i64, usize)>,
+    uses_requests: bool,
+    // acquisitions without request before the first request of the trace, only buffered since the check is opt-in
+    unrequested: Vec<AnalyzerError>,
+    violations: Vec<AnalyzerError>,
+}
+
+impl Analysis for Requests {
+    fn on_event(&mut self, row: usize, event: &Event) {
+        let thread_id = event.thread_identifier;
+        let pending = self.pending.remove(&thread_id);
+
+        if let Some((lock_id, request_row)) = pending {
+            let is_matching_acquisition =
+                event.operation == Operation::Acquire && event.operand.id() == Some(lock_id);
+
+            if !is_matching_acquisition {
+                self.violations.push(AnalyzerError::DanglingRequest {
+                    row: request_row,
+                    lock_id,
+                    thread_id,
+                });
+            }
+        }
+
+        match event.operation {
+            Operation::Request => {
+                self.uses_requests = true;
+                self.pending
+                    .insert(thread_id, (event.operand.id().unwrap(), row));
+            }
+            Operation::Acquire => {
+                let lock_id = event.operand.id().unwrap();
+
+                if pending.map(|(requested, _)| requested) != Some(lock_id) {
+                    let error = AnalyzerError::AcquiredWithoutRequest {
+                        row,
+                        lock_id,
+                        thread_id,
+                    };
+
+                    // acquisitions are only checked if the trace uses requests at all
+                    if self.uses_requests {
+                        self.violations.push(error);
+                    } else {
+                        self.unrequested.push(error);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn finish(self: Box<Self>) -> Report {
+        let mut violations = Vec::new();
+
+        if self.uses_requests {
+            violations.extend(self.unrequested);
+        }
+
+        violations.extend(self.violations);
+
+        let mut dangling = self.pending.into_iter().collect::<Vec<_>>();
+        dangling.sort_by_key(|(_, (_, row))| *row);
+
+        for (thread_id, (lock_id, row)) in dangling {
+            violations.push(AnalyzerError::DanglingRequest {
+                row,
+                lock_id,
+                thread_id,
+            });
+        }
+
+        Report {
+            violations,
+            ..Report::default()
+        }
+    }
+}
@@ -0,0 +1,67 @@
+use crate::analysis::{Analysis, Report};
+use crate::parser::{Event, Operand};
+use crate::reader::TraceFormat;
+use serde::Serialize;
+use std::collections::HashSet;
+
+/// General information about an analyzed trace
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+pub struct TraceMetadata {
+    /// the format of the trace, None if the events were not streamed via a `TraceReader`
+    pub format: Option<TraceFormat>,
+    pub threads: usize,
+    /// the largest thread identifier, None if the trace contains no events
+    pub max_thread_id: Option<i64>,
+    pub locks: usize,
+    /// the largest lock identifier, None if the trace contains no locks
+    pub max_lock_id: Option<i64>,
+    pub variables: usize,
+    /// the largest memory location, None if the trace contains no memory accesses
+    pub max_variable_id: Option<i64>,
+    pub events: usize,
+}
+
+/// Counts the distinct threads, locks and memory locations of a trace
+#[derive(Default)]
+pub struct Statistics {
+    threads: HashSet<i64>,
+    locks: HashSet<i64>,
+    variables: HashSet<i64>,
+    events: usize,
+}
+
+impl Analysis for Statistics {
+    fn on_event(&mut self, _row: usize, event: &Event) {
+        self.events += 1;
+        self.threads.insert(event.thread_identifier);
+
+        match event.operand {
+            Operand::MemoryLocation(location) => {
+                self.variables.insert(location);
+            }
+            Operand::LockIdentifier(lock_id) => {
+                self.locks.insert(lock_id);
+            }
+            Operand::ThreadIdentifier(thread_id) => {
+                self.threads.insert(thread_id);
+            }
+            Operand::None => {}
+        }
+    }
+
+    fn finish(self: Box<Self>) -> Report {
+        Report {
+            metadata: Some(TraceMetadata {
+                format: None,
+                threads: self.threads.len(),
+                max_thread_id: self.threads.iter().max().copied(),
+                locks: self.locks.len(),
+                max_lock_id: self.locks.iter().max().copied(),
+                variables: self.variables.len(),
+                max_variable_id: self.variables.iter().max().copied(),
+                events: self.events,
+            }),
+            ..Report::default()
+        }
+    }
+}
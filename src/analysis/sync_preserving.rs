@@ -0,0 +1,240 @@
+use crate::analysis::vector_clock::{ThreadIndex, VectorClock};
+use crate::parser::{Event, Operation};
+use std::collections::HashMap;
+
+/// A concrete acquisition of a lock which may be part of a deadlock
+#[derive(Clone, Debug)]
+pub(crate) struct Instance {
+    pub row: usize,
+    thread: usize,
+    // the position of the acquisition among the events of its thread, starting at 0
+    position: u32,
+    // the events which have to precede the acquisition in every reordering
+    clock: VectorClock,
+}
+
+struct CriticalSection {
+    thread: usize,
+    acquire: u32,
+    release: Option<VectorClock>,
+    // the amount of nested acquisitions by the owner
+    depth: usize,
+}
+
+/// Decides whether a deadlock pattern is reachable by a sync-preserving reordering of the trace (SPD)
+///
+/// A reordering is sync-preserving if it keeps the thread order, the order of fork and join events, the writer of
+/// every read and the order of the critical sections on the same lock it contains. The smallest set of events which is closed
+/// under these constraints is represented by a vector clock holding the amount of included events of every thread.
+#[derive(Default)]
+pub(crate) struct SyncPreserving {
+    threads: ThreadIndex,
+    // the clock of every thread which is only ordered by the thread order, fork, join and read-from
+    clocks: Vec<VectorClock>,
+    writes: HashMap<i64, VectorClock>,
+    // the critical sections of every lock in the order of the trace
+    critical_sections: HashMap<i64, Vec<CriticalSection>>,
+}
+
+impl SyncPreserving {
+    /// Returns the index of a thread and makes sure it has a clock
+    fn thread(&mut self, thread_id: i64) -> usize {
+        let thread = self.threads.index(thread_id);
+
+        if self.clocks.len() <= thread {
+            self.clocks.resize(thread + 1, VectorClock::default());
+        }
+
+        thread
+    }
+
+    /// Returns the acquisition a thread is about to perform in a row, has to be called before `on_event`
+    ///
+    /// # Arguments
+    ///
+    /// * `row`: the row of the acquisition
+    /// * `thread_id`: the id of the acquiring thread
+    ///
+    /// returns: Instance the acquisition together with all events which have to precede it
+    ///
+    pub fn instance(&mut self, row: usize, thread_id: i64) -> Instance {
+        let thread = self.thread(thread_id);
+        let clock = self.clocks[thread].clone();
+
+        Instance {
+            row,
+            thread,
+            position: clock.get(thread),
+            clock,
+        }
+    }
+
+    /// Orders an event after its predecessors
+    ///
+    /// # Arguments
+    ///
+    /// * `event`: the to be ordered event
+    ///
+    /// returns: () unit
+    ///
+    pub fn on_event(&mut self, event: &Event) {
+        let thread = self.thread(event.thread_identifier);
+        let Some(operand) = event.operand.id() else {
+            self.clocks[thread].increment(thread);
+            return;
+        };
+
+        let position = self.clocks[thread].get(thread);
+        self.clocks[thread].increment(thread);
+
+        match event.operation {
+            Operation::Read => {
+                if let Some(write) = self.writes.get(&operand) {
+                    self.clocks[thread].join(write);
+                }
+            }
+            Operation::Write => {
+                self.writes.insert(operand, self.clocks[thread].clone());
+            }
+            Operation::Fork => {
+                let child = self.thread(operand);
+                let parent_clock = self.clocks[thread].clone();
+
+                self.clocks[child].join(&parent_clock);
+            }
+            Operation::Join => {
+                let child = self.thread(operand);
+                let child_clock = self.clocks[child].clone();
+
+                self.clocks[thread].join(&child_clock);
+            }
+            Operation::Acquire => {
+                let sections = self.critical_sections.entry(operand).or_default();
+
+                match open_section(sections, thread) {
+                    Some(section) => section.depth += 1,
+                    None => sections.push(CriticalSection {
+                        thread,
+                        acquire: position,
+                        release: None,
+                        depth: 1,
+                    }),
+                }
+            }
+            Operation::Release => {
+                let section = self
+                    .critical_sections
+                    .get_mut(&operand)
+                    .and_then(|sections| open_section(sections, thread));
+
+                if let Some(section) = section {
+                    section.depth -= 1;
+
+                    if section.depth == 0 {
+                        section.release = Some(self.clocks[thread].clone());
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Computes the smallest sync-preserving closed set of events containing a set of events
+    ///
+    /// # Arguments
+    ///
+    /// * `frontier`: the set of events as the amount of included events of every thread
+    ///
+    /// returns: Option<VectorClock> the closed set, None if it would contain a critical section after one which is never released
+    ///
+    fn closure(&self, mut frontier: VectorClock) -> Option<VectorClock> {
+        loop {
+            let mut changed = false;
+
+            for sections in self.critical_sections.values() {
+                let mut included = sections
+                    .iter()
+                    .filter(|section| frontier.get(section.thread) > section.acquire)
+                    .collect::<Vec<_>>();
+
+                // the order of the included critical sections is preserved, so all except the last one are complete
+                included.pop();
+
+                for section in included {
+                    let release = section.release.as_ref()?;
+
+                    if !release.is_before(&frontier) {
+                        frontier.join(release);
+                        changed = true;
+                    }
+                }
+            }
+
+            if !changed {
+                return Some(frontier);
+            }
+        }
+    }
+
+    /// Searches for concrete acquisitions of a deadlock pattern which are reachable by a sync-preserving reordering
+    ///
+    /// # Arguments
+    ///
+    /// * `instances`: the concrete acquisitions of every abstract acquisition of the pattern in the order of the trace
+    ///
+    /// returns: Option<Vec<&Instance, Global>> one acquisition per abstract acquisition which deadlock, None if no reordering reaches the deadlock
+    ///
+    pub fn witness<'a>(&self, instances: &[&'a [Instance]]) -> Option<Vec<&'a Instance>> {
+        let mut chosen = vec![0; instances.len()];
+
+        loop {
+            let pattern = instances
+                .iter()
+                .zip(&chosen)
+                .map(|(candidates, index)| candidates.get(*index))
+                .collect::<Option<Vec<_>>>()?;
+
+            let mut frontier = VectorClock::default();
+
+            for instance in &pattern {
+                frontier.join(&instance.clock);
+            }
+
+            // a critical section which is never released can't be completed, so the latest acquisition which
+            // brought it into the closure is skipped
+            let Some(closure) = self.closure(frontier) else {
+                let latest = (0..pattern.len()).max_by_key(|index| pattern[*index].row)?;
+                chosen[latest] += 1;
+
+                continue;
+            };
+
+            // an acquisition inside of the closure can't be enabled, a later one of the same thread has to be tried
+            // since the closure only grows with later acquisitions
+            match pattern
+                .iter()
+                .position(|instance| closure.get(instance.thread) > instance.position)
+            {
+                Some(index) => chosen[index] += 1,
+                None => return Some(pattern),
+            }
+        }
+    }
+}
+
+/// Returns the critical section a thread currently holds, which is not necessarily the last one of the lock since the
+/// sections of a malformed trace may overlap
+///
+/// # Arguments
+///
+/// * `sections`: the critical sections of a lock in the order of the trace
+/// * `thread`: the index of the thread
+///
+/// returns: Option<&mut CriticalSection> the open section of the thread, None if it doesn't hold the lock
+///
+fn open_section(sections: &mut [CriticalSection], thread: usize) -> Option<&mut CriticalSection> {
+    sections
+        .iter_mut()
+        .rev()
+        .find(|section| section.thread == thread && section.release.is_none())
+}
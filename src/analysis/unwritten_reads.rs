@@ -0,0 +1,41 @@
+use crate::analysis::{Analysis, Report};
+use crate::error::AnalyzerError;
+use crate::parser::{Event, Operation};
+use std::collections::HashSet;
+
+/// Checks that memory locations are written before they are read
+#[derive(Default)]
+pub struct UnwrittenReads {
+    written: HashSet<i64>,
+    violations: Vec<AnalyzerError>,
+}
+
+impl Analysis for UnwrittenReads {
+    fn on_event(&mut self, row: usize, event: &Event) {
+        match event.operation {
+            Operation::Read => {
+                let location = event.operand.id().unwrap();
+
+                if !self.written.contains(&location) {
+                    self.violations
+                        .push(AnalyzerError::ReadFromUnwrittenMemory {
+                            row,
+                            thread_id: event.thread_identifier,
+                            location,
+                        });
+                }
+            }
+            Operation::Write => {
+                self.written.insert(event.operand.id().unwrap());
+            }
+            _ => {}
+        }
+    }
+
+    fn finish(self: Box<Self>) -> Report {
+        Report {
+            violations: self.violations,
+            ..Report::default()
+        }
+    }
+}
@@ -0,0 +1,92 @@
+use std::collections::HashMap;
+
+/// A vector clock indexed by the dense index of a thread
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub(crate) struct VectorClock(Vec<u32>);
+
+impl VectorClock {
+    /// Creates the initial clock of a thread
+    ///
+    /// # Arguments
+    ///
+    /// * `thread`: the index of the thread
+    ///
+    /// returns: VectorClock a clock where only the component of the thread is 1
+    ///
+    pub fn new(thread: usize) -> Self {
+        let mut clock = Self::default();
+        clock.set(thread, 1);
+
+        clock
+    }
+
+    pub fn get(&self, thread: usize) -> u32 {
+        self.0.get(thread).copied().unwrap_or(0)
+    }
+
+    pub fn set(&mut self, thread: usize, value: u32) {
+        if self.0.len() <= thread {
+            self.0.resize(thread + 1, 0);
+        }
+
+        self.0[thread] = value;
+    }
+
+    pub fn increment(&mut self, thread: usize) {
+        self.set(thread, self.get(thread) + 1);
+    }
+
+    /// Returns if no component of this clock is greater than the one of another clock
+    pub fn is_before(&self, other: &VectorClock) -> bool {
+        self.0
+            .iter()
+            .enumerate()
+            .all(|(thread, value)| *value <= other.get(thread))
+    }
+
+    /// Sets every component to the maximum of both clocks
+    pub fn join(&mut self, other: &VectorClock) {
+        if self.0.len() < other.0.len() {
+            self.0.resize(other.0.len(), 0);
+        }
+
+        for (own, other) in self.0.iter_mut().zip(other.0.iter()) {
+            *own = (*own).max(*other);
+        }
+    }
+}
+
+/// Maps the ids of threads to dense indices suitable for vector clocks
+#[derive(Default)]
+pub(crate) struct ThreadIndex {
+    indices: HashMap<i64, usize>,
+}
+
+impl ThreadIndex {
+    /// Returns the index of a thread, a new index is assigned if the thread is unknown
+    pub fn index(&mut self, thread_id: i64) -> usize {
+        let next = self.indices.len();
+
+        *self.indices.entry(thread_id).or_insert(next)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn succeed_when_joining_clocks() {
+        // arrange
+        let mut first = VectorClock::new(0);
+        let second = VectorClock::new(2);
+
+        // act
+        first.join(&second);
+
+        // assert
+        assert_eq!(first.get(0), 1);
+        assert_eq!(first.get(1), 0);
+        assert_eq!(first.get(2), 1);
+    }
+}
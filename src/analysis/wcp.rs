@@ -0,0 +1,358 @@
+use crate::analysis::races::{Access, AccessHistory};
+use crate::analysis::vector_clock::{ThreadIndex, VectorClock};
+use crate::analysis::{Analysis, Report};
+use crate::parser::{Event, Operation};
+use std::collections::{HashMap, HashSet, VecDeque};
+
+// a critical section as seen by the threads which acquire the same lock later
+struct CriticalSection {
+    // the weak-causally-precedes clock of the acquisition
+    acquire: VectorClock,
+    // the happens-before clock of the release
+    release: Option<VectorClock>,
+}
+
+// the critical sections of a thread on a lock which might still order a later release of another thread
+#[derive(Default)]
+struct CriticalSections {
+    // the amount of dropped sections, which is the index of the first kept one
+    dropped: usize,
+    kept: VecDeque<CriticalSection>,
+}
+
+#[derive(Default)]
+struct Lock {
+    // the clocks of the last release
+    happens_before: VectorClock,
+    predecessors: VectorClock,
+    // the happens-before clocks of all releases of critical sections which read or wrote a memory location
+    reads: HashMap<i64, VectorClock>,
+    writes: HashMap<i64, VectorClock>,
+    // the critical sections of every thread in the order of their acquisition
+    critical_sections: HashMap<usize, CriticalSections>,
+}
+
+#[derive(Default)]
+struct Thread {
+    happens_before: VectorClock,
+    // the events which weak-causally precede the next event of the thread, without the thread itself
+    predecessors: VectorClock,
+    // the held locks in the order of their acquisition, nested acquisitions included
+    held_locks: Vec<i64>,
+    // the memory locations read and written in the critical section of every held lock
+    reads: HashMap<i64, HashSet<i64>>,
+    writes: HashMap<i64, HashSet<i64>>,
+    // the first critical section of every lock and other thread which might still order a later release of the thread
+    pending: HashMap<(i64, usize), usize>,
+}
+
+/// Detects data races via the weak-causally-precedes relation using vector clocks (WCP)
+///
+/// Other than happens-before, critical sections on the same lock are only ordered if they contain conflicting
+/// accesses, which allows to predict races in reorderings of the trace.
+#[derive(Default)]
+pub struct WeakCausallyPrecedes {
+    threads: ThreadIndex,
+    states: Vec<Thread>,
+    locks: HashMap<i64, Lock>,
+    history: AccessHistory,
+}
+
+impl WeakCausallyPrecedes {
+    /// Returns the index of a thread and makes sure it has a state
+    fn thread(&mut self, thread_id: i64) -> usize {
+        let thread = self.threads.index(thread_id);
+
+        while self.states.len() <= thread {
+            self.states.push(Thread {
+                happens_before: VectorClock::new(self.states.len()),
+                ..Thread::default()
+            });
+        }
+
+        thread
+    }
+
+    /// Returns the clock used to check the accesses of a thread
+    fn clock(&self, thread: usize) -> VectorClock {
+        let state = &self.states[thread];
+        let mut clock = state.predecessors.clone();
+        clock.set(thread, state.happens_before.get(thread));
+
+        clock
+    }
+
+    fn acquire(&mut self, thread: usize, lock_id: i64) {
+        let state = &mut self.states[thread];
+        let is_nested = state.held_locks.contains(&lock_id);
+        state.held_locks.push(lock_id);
+
+        if is_nested {
+            return;
+        }
+
+        let lock = self.locks.entry(lock_id).or_default();
+        state.happens_before.join(&lock.happens_before);
+        state.predecessors.join(&lock.predecessors);
+        state.reads.insert(lock_id, HashSet::new());
+        state.writes.insert(lock_id, HashSet::new());
+
+        let acquire = self.clock(thread);
+        let lock = self.locks.get_mut(&lock_id).unwrap();
+
+        lock.critical_sections
+            .entry(thread)
+            .or_default()
+            .kept
+            .push_back(CriticalSection {
+                acquire,
+                release: None,
+            });
+    }
+
+    fn release(&mut self, thread: usize, lock_id: i64) {
+        let state = &mut self.states[thread];
+
+        let Some(index) = state.held_locks.iter().rposition(|held| *held == lock_id) else {
+            return;
+        };
+
+        state.held_locks.remove(index);
+
+        if state.held_locks.contains(&lock_id) {
+            return;
+        }
+
+        let Some(lock) = self.locks.get_mut(&lock_id) else {
+            return;
+        };
+
+        // a release is ordered after the releases of all critical sections whose acquisition precedes it
+        let mut clock = state.predecessors.clone();
+        clock.set(thread, state.happens_before.get(thread));
+
+        // the sections of every other thread are consumed independently, until no further section is ordered
+        let mut is_ordered = true;
+
+        while is_ordered {
+            is_ordered = false;
+
+            for (other, sections) in &lock.critical_sections {
+                if *other == thread {
+                    continue;
+                }
+
+                let pending = state.pending.entry((lock_id, *other)).or_default();
+                *pending = (*pending).max(sections.dropped);
+
+                while let Some(CriticalSection {
+                    acquire,
+                    release: Some(release),
+                }) = sections.kept.get(*pending - sections.dropped)
+                {
+                    if !acquire.is_before(&clock) {
+                        break;
+                    }
+
+                    state.predecessors.join(release);
+                    clock.join(release);
+                    *pending += 1;
+                    is_ordered = true;
+                }
+            }
+        }
+
+        // conflicting accesses order the critical sections of the same lock
+        for location in state.reads.remove(&lock_id).unwrap_or_default() {
+            lock.reads
+                .entry(location)
+                .or_default()
+                .join(&state.happens_before);
+        }
+
+        for location in state.writes.remove(&lock_id).unwrap_or_default() {
+            lock.writes
+                .entry(location)
+                .or_default()
+                .join(&state.happens_before);
+        }
+
+        lock.happens_before = state.happens_before.clone();
+        lock.predecessors = state.predecessors.clone();
+
+        if let Some(section) = lock
+            .critical_sections
+            .get_mut(&thread)
+            .and_then(|sections| sections.kept.back_mut())
+        {
+            section.release = Some(state.happens_before.clone());
+        }
+
+        // every later acquisition of the lock joins its predecessors, so sections released before them can't order
+        // anything anymore
+        for sections in lock.critical_sections.values_mut() {
+            while sections.kept.front().is_some_and(|section| {
+                section
+                    .release
+                    .as_ref()
+                    .is_some_and(|release| release.is_before(&lock.predecessors))
+            }) {
+                sections.kept.pop_front();
+                sections.dropped += 1;
+            }
+        }
+
+        state.happens_before.increment(thread);
+    }
+
+    fn access(&mut self, row: usize, thread: usize, event: &Event) {
+        let location = event.operand.id().unwrap();
+        let is_read = event.operation == Operation::Read;
+        let state = &mut self.states[thread];
+
+        for lock_id in &state.held_locks {
+            let Some(lock) = self.locks.get(lock_id) else {
+                continue;
+            };
+
+            // a read conflicts with earlier writes, a write with earlier reads and writes
+            let mut conflicting = vec![lock.writes.get(&location)];
+
+            if !is_read {
+                conflicting.push(lock.reads.get(&location));
+            }
+
+            for clock in conflicting.into_iter().flatten() {
+                state.predecessors.join(clock);
+            }
+
+            let accessed = if is_read {
+                &mut state.reads
+            } else {
+                &mut state.writes
+            };
+
+            if let Some(locations) = accessed.get_mut(lock_id) {
+                locations.insert(location);
+            }
+        }
+
+        let access = Access {
+            row,
+            thread_id: event.thread_identifier,
+            operation: event.operation.clone(),
+            loc: event.loc,
+        };
+        let clock = self.clock(thread);
+
+        if is_read {
+            self.history.read(location, thread, &clock, access);
+        } else {
+            self.history.write(location, thread, &clock, access);
+        }
+    }
+}
+
+impl Analysis for WeakCausallyPrecedes {
+    fn on_event(&mut self, row: usize, event: &Event) {
+        let thread = self.thread(event.thread_identifier);
+        let operand = event.operand.id();
+
+        match event.operation {
+            Operation::Read | Operation::Write => self.access(row, thread, event),
+            Operation::Acquire => self.acquire(thread, operand.unwrap()),
+            Operation::Release => self.release(thread, operand.unwrap()),
+            Operation::Fork => {
+                // fork and join order the threads like happens-before
+                let child = self.thread(operand.unwrap());
+                let parent_clock = self.clock(thread);
+                let parent_happens_before = self.states[thread].happens_before.clone();
+
+                self.states[child].predecessors.join(&parent_clock);
+                self.states[child]
+                    .happens_before
+                    .join(&parent_happens_before);
+                self.states[thread].happens_before.increment(thread);
+            }
+            Operation::Join => {
+                let child = self.thread(operand.unwrap());
+                let child_clock = self.clock(child);
+                let child_happens_before = self.states[child].happens_before.clone();
+
+                self.states[thread].predecessors.join(&child_clock);
+                self.states[thread]
+                    .happens_before
+                    .join(&child_happens_before);
+                self.states[child].happens_before.increment(child);
+            }
+            _ => {}
+        }
+    }
+
+    fn finish(self: Box<Self>) -> Report {
+        Report {
+            races: self.history.into_races(),
+            ..Report::default()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::Operand;
+
+    fn event(thread_identifier: i64, operation: Operation, operand: Operand) -> Event {
+        Event {
+            thread_identifier,
+            operation,
+            operand,
+            loc: 0,
+        }
+    }
+
+    #[test]
+    fn succeed_when_dropping_ordered_critical_sections() {
+        // arrange
+        let mut analysis = WeakCausallyPrecedes::default();
+        let mut events = Vec::new();
+
+        for _ in 0..100 {
+            for thread_id in [1, 2] {
+                events.push(event(
+                    thread_id,
+                    Operation::Acquire,
+                    Operand::LockIdentifier(1),
+                ));
+                events.push(event(
+                    thread_id,
+                    Operation::Write,
+                    Operand::MemoryLocation(1),
+                ));
+                events.push(event(
+                    thread_id,
+                    Operation::Release,
+                    Operand::LockIdentifier(1),
+                ));
+            }
+        }
+
+        // act
+        for (row, event) in (1..).zip(events.iter()) {
+            analysis.on_event(row, event);
+        }
+
+        // assert
+        let kept = analysis.locks[&1]
+            .critical_sections
+            .values()
+            .map(|sections| sections.kept.len())
+            .sum::<usize>();
+
+        assert!(kept <= 2);
+
+        let report = Box::new(analysis).finish();
+
+        assert!(report.races.is_empty());
+    }
+}
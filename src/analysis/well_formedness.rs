@@ -0,0 +1,90 @@
+use crate::analysis::locks::LockTable;
+use crate::analysis::{Analysis, Report};
+use crate::arguments::LockSemantics;
+use crate::error::AnalyzerError;
+use crate::parser::{Event, Operation};
+
+/// Checks that locks are only acquired when free, only released by their owner and not held by joined threads
+#[derive(Default)]
+pub struct WellFormedness {
+    locks: LockTable,
+    // if locks which are still held at the end of the trace are violations
+    held_locks: bool,
+    violations: Vec<AnalyzerError>,
+}
+
+impl WellFormedness {
+    pub fn new(lock_semantics: LockSemantics, held_locks: bool) -> Self {
+        Self {
+            locks: LockTable::new(lock_semantics),
+            held_locks,
+            ..Self::default()
+        }
+    }
+
+    /// Checks that a joined thread does not hold any lock
+    ///
+    /// # Arguments
+    ///
+    /// * `row`: the current row of the trace
+    /// * `thread_id`: the id of the joining thread
+    /// * `child_id`: the id of the joined thread
+    ///
+    /// returns: () unit
+    ///
+    fn join(&mut self, row: usize, thread_id: i64, child_id: i64) {
+        for (lock_id, owner, acquired) in self.locks.held_locks() {
+            if owner == child_id {
+                self.violations.push(AnalyzerError::JoinedThreadHoldsLock {
+                    row,
+                    thread_id,
+                    child_id,
+                    lock_id,
+                    acquired,
+                });
+            }
+        }
+    }
+}
+
+impl Analysis for WellFormedness {
+    fn on_event(&mut self, row: usize, event: &Event) {
+        let result = match event.operation {
+            Operation::Acquire => {
+                self.locks
+                    .acquire(event.thread_identifier, event.operand.id().unwrap(), row)
+            }
+            Operation::Release => {
+                self.locks
+                    .release(event.thread_identifier, event.operand.id().unwrap(), row)
+            }
+            Operation::Join => {
+                self.join(row, event.thread_identifier, event.operand.id().unwrap());
+                Ok(())
+            }
+            // other operations are not needed to check well-formedness
+            _ => Ok(()),
+        };
+
+        if let Err(error) = result {
+            self.violations.push(error);
+        }
+    }
+
+    fn finish(mut self: Box<Self>) -> Report {
+        if self.held_locks {
+            for (lock_id, owner, row) in self.locks.held_locks() {
+                self.violations.push(AnalyzerError::LockHeldAtEnd {
+                    lock_id,
+                    owner,
+                    row,
+                });
+            }
+        }
+
+        Report {
+            violations: self.violations,
+            ..Report::default()
+        }
+    }
+}
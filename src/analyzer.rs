@@ -1,44 +1,32 @@
-use crate::arguments::Arguments;
+use crate::analysis::{
+    run_analyses, Analysis, AtomicBlocks, Atomicity, ForkJoin, Goodlock, HappensBefore,
+    LockDependencies, LockGraph, Lockset, Report, Requests, Statistics, TraceMetadata,
+    UnwrittenReads, WeakCausallyPrecedes, WellFormedness,
+};
+use crate::arguments::{Arguments, ConvertArguments, FindingKind, OutputFormat, RaceMode};
 use crate::error::AnalyzerError;
-use crate::lexer::tokenize_source;
-use crate::parser::{parse_event, Event, Operand, Operation};
-use log::{debug, info};
-use std::collections::{HashMap, HashSet};
-use std::fmt::Write as FmtWrite;
+use crate::reader::{TraceFormat, TraceHeader, TraceReader, STDIN_PATH};
+use crate::writer::TraceWriter;
 use std::fs;
 use std::fs::File;
-use std::io::{BufRead, BufReader, Read, Write};
+use std::io;
+use std::io::{BufWriter, ErrorKind, Write};
 use std::path::Path;
-
-struct Lock {
-    owner: Option<i64>,
-    locked: bool,
-    row: usize,
-}
-
-// used for the GraphViz representation
-#[derive(Eq, Hash, PartialEq)]
-struct Edge {
-    from: i64,
-    to: i64,
-}
-
-// used for the GraphViz representation
-type Graph = HashMap<i64, HashSet<i64>>;
-
-#[derive(Eq, PartialEq, Debug, Clone)]
-struct LockDependency {
-    thread_id: i64,
-    lock_id: i64,
-    acquired_locks: HashSet<i64>,
-    line: usize,
-}
-
-impl LockDependency {
-    fn remove_lock(&mut self, lock_id: i64) {
-        self.acquired_locks.remove(&lock_id);
-    }
-}
+use std::process;
+
+// the directory of the GraphViz representations if no path is given
+const OUTPUT_DIRECTORY: &str = "output";
+
+/// The trace is well-formed and no failing finding was detected
+pub const EXIT_SUCCESS: i32 = 0;
+/// The trace violates its well-formedness
+pub const EXIT_VIOLATIONS: i32 = 1;
+/// The trace or another input could not be read
+pub const EXIT_INPUT_ERROR: i32 = 2;
+/// An analysis detected deadlocks, races or other findings
+pub const EXIT_FINDINGS: i32 = 3;
+/// The results or the converted trace could not be written
+pub const EXIT_OUTPUT_ERROR: i32 = 4;
 
 /// Analyzes a trace for well-formedness
 ///
@@ -49,578 +37,345 @@ impl LockDependency {
 /// returns: Result<(), Vec<AnalyzerError, Global>> Unit if the trace is well-formed, otherwise a vector containing the violations
 ///
 pub fn analyze_trace(arguments: &Arguments) -> Result<(), Vec<AnalyzerError>> {
-    // store trace violations
-    let mut errors: Vec<AnalyzerError> = Vec::new();
-    let mut locks: HashMap<i64, Lock> = HashMap::new();
-    let row = 1;
-
-    let file_handle = match File::open(&arguments.input) {
-        Ok(file_handle) => file_handle,
-        Err(err) => {
-            errors.push(AnalyzerError::from(err));
-            return Err(errors);
-        }
-    };
-
-    // stream content of file to avoid OOM
-    let mut trace_reader = BufReader::new(file_handle);
-
-    let file_extension = Path::new(&arguments.input)
-        .extension()
-        .and_then(|ext| ext.to_str());
+    let report = analyze(arguments);
 
-    // create graphviz representation
-    let mut graphviz_locks = String::new(); // create graphical representation of the relation between the locks of a trace
-    let mut graphviz_threads = String::new(); // create graphical representation of the relation between the threads of a trace
-
-    let mut lockgraph = HashSet::<Edge>::new();
-    let mut lock_dependencies = Vec::<LockDependency>::new();
-
-    if &arguments.graph == &true {
-        writeln!(&mut graphviz_locks, "digraph G {{").unwrap();
-    }
-
-    // analyze either a STD or RapidBin trace
-    match file_extension {
-        Some("std") => analyze_std_trace(
-            &arguments,
-            &mut trace_reader,
-            &mut errors,
-            &mut locks,
-            row,
-            &mut lockgraph,
-            &mut lock_dependencies,
-        ),
-        Some("data") => analyze_rapid_trace(
-            &arguments,
-            &mut trace_reader,
-            &mut errors,
-            &mut locks,
-            row,
-            &mut lockgraph,
-            &mut lock_dependencies,
-        ),
-        _ => errors.push(AnalyzerError::UnsupportedFileExtension),
+    if report.violations.is_empty() {
+        return Ok(());
     }
 
-    if &arguments.graph == &true {
-        for entry in lockgraph.drain() {
-            writeln!(&mut graphviz_locks, "    L{} -> L{};", entry.from, entry.to).unwrap();
-        }
-
-        writeln!(&mut graphviz_locks, "}}").unwrap();
+    Err(report.violations)
+}
 
-        match fs::create_dir_all("output") {
-            Ok(()) => {
-                let mut file = File::create("output/graphviz_locks.txt").unwrap();
-                file.write_all(graphviz_locks.as_bytes()).unwrap();
-            },
-            Err(e) => 
-                eprintln!("Failed to create directory {:?}: {}", "output", e)
+/// Runs all analyses requested by the command line arguments over a trace
+///
+/// # Arguments
+///
+/// * `arguments`: the command line arguments
+///
+/// returns: Report the results of the analyses, a trace which could not be opened is reported as violation
+///
+pub fn analyze(arguments: &Arguments) -> Report {
+    let mut trace_reader = match TraceReader::open_with(
+        &arguments.input,
+        arguments.input_format,
+        arguments.normalize,
+    ) {
+        Ok(trace_reader) => trace_reader.strict(arguments.strict),
+        Err(err) => {
+            return Report {
+                violations: vec![err],
+                ..Report::default()
+            }
         }
-    }
+    };
 
-    if &arguments.lock_dependencies == &true {
-        writeln!(&mut graphviz_threads, "digraph G {{").unwrap();
+    let mut report = run_analyses(&mut trace_reader, analyses_of(arguments));
 
-        let mut graph = HashMap::<i64, HashSet<i64>>::new();
+    if let Some(metadata) = &mut report.metadata {
+        metadata.format = Some(trace_reader.format());
 
-        // clean lock dependencies to rule out false positives (like a cycle where the locks are owned be the identical thread)
-        for entry in &lock_dependencies {
-            let children = lock_dependencies
-                .iter()
-                .filter(|other| {
-                    other.thread_id != entry.thread_id
-                        && other
-                            .acquired_locks
-                            .intersection(&entry.acquired_locks)
-                            .count()
-                            == 0
-                }) // no guard locks
-                .filter(|other| other.acquired_locks.contains(&entry.lock_id))
-                .map(|dependency| dependency.thread_id) // check for chain
-                .collect::<HashSet<_>>();
-
-            // save information in GraphViz syntax
-            for child in children {
-                add_edge(&mut graph, entry.thread_id, child);
-                writeln!(
-                    &mut graphviz_threads,
-                    "    T{} -> T{};",
-                    entry.thread_id, child
-                )
-                .unwrap();
-            }
-
-            debug!("{:?}", entry);
+        if let Some(header) = trace_reader.header() {
+            report
+                .violations
+                .extend(header.validate(metadata, trace_reader.records()));
         }
+    }
 
-        writeln!(&mut graphviz_threads, "}}").unwrap();
+    let outputs = [
+        (&report.lock_graph, &arguments.graph_out, "locks"),
+        (&report.thread_graph, &arguments.deps_out, "threads"),
+    ];
+
+    let mut errors = Vec::new();
+
+    for (graphviz, path, suffix) in outputs {
+        if let Some(graphviz) = graphviz {
+            let path = output_path(path.as_deref(), &arguments.input, suffix);
+
+            // the standard output only holds the JSON or SARIF document of the report in these formats
+            if path == STDIN_PATH && arguments.format != OutputFormat::Text {
+                errors.push(AnalyzerError::OutputError {
+                    path,
+                    error: io::Error::new(
+                        ErrorKind::InvalidInput,
+                        "the standard output is reserved for the report unless the format is text",
+                    ),
+                });
 
-        let result = validate_dependency_graph(graph);
+                continue;
+            }
 
-        info!("{:?} deadlocks were identified", result);
+            let result = write_output(&path, |sink| {
+                sink.write_all(graphviz.as_bytes())
+                    .map_err(|err| AnalyzerError::from(err).at_output(&path))
+            });
 
-        match fs::create_dir_all("output") {
-            Ok(()) => {
-                let mut file2 = File::create("output/graphviz_threads.txt").unwrap();
-                file2.write_all(graphviz_threads.as_bytes()).unwrap();
-            },
-            Err(e) => 
-                eprintln!("Failed to create directory {:?}: {}", "output", e)
+            if let Err(err) = result {
+                errors.push(err);
+            }
         }
     }
 
-    if errors.is_empty() {
-        return Ok(());
-    }
-
-    Err(errors)
-}
+    report.violations.extend(errors);
 
-/// Helper function to create a graph structure to represent a trace in GraphViz format
-///
-/// # Arguments
-///
-/// * `graph`: the current graph structure of a trace
-/// * `from`: the origin of an edge
-/// * `to`: the target of an edge
-///
-/// returns: ()
-///
-fn add_edge(graph: &mut Graph, from: i64, to: i64) {
-    graph.entry(from).or_insert_with(HashSet::new).insert(to);
+    report
 }
 
-/// Analyzes a trace written in STD format
+/// Converts a trace between the STD and RapidBin format
 ///
 /// # Arguments
 ///
-/// * `arguments`: the command line arguments
-/// * `trace_reader`: a buffered reader containing the contents of a STD trace
-/// * `errors`: a vector containing the errors the analyzer encountered
-/// * `locks`: a vector containing all locks of the trace
-/// * `row`: the current row of the trace
-/// * `graphviz`: a hashset containing edges for the GraphViz representation of a trace
-/// * `lock_dependencies`: a vector containing the lock dependencies of a trace
+/// * `arguments`: the command line arguments of the conversion
 ///
-/// returns: () unit
+/// returns: Result<TraceMetadata, AnalyzerError> the counts of the converted trace, an error if the trace could not be
+/// read or an event can't be represented in the target format
 ///
-fn analyze_std_trace(
-    arguments: &Arguments,
-    trace_reader: &mut BufReader<File>,
-    errors: &mut Vec<AnalyzerError>,
-    locks: &mut HashMap<i64, Lock>,
-    mut row: usize,
-    graphviz: &mut HashSet<Edge>,
-    lock_dependencies: &mut Vec<LockDependency>,
-) {
-    for line in trace_reader.lines() {
-        let line = match line.map_err(AnalyzerError::from) {
-            Ok(line) => line,
-            Err(err) => return errors.push(AnalyzerError::from(err)),
-        };
+pub fn convert(arguments: &ConvertArguments) -> Result<TraceMetadata, AnalyzerError> {
+    let to = match arguments.to {
+        Some(format) => format,
+        None => TraceFormat::from_extension(&arguments.output)
+            .ok_or(AnalyzerError::UnsupportedFileExtension)?,
+    };
 
-        let tokens = match tokenize_source(line, arguments.normalize) {
-            Ok(tokens) => tokens,
-            Err(err) => return errors.push(AnalyzerError::from(err)),
-        };
+    let header = match to {
+        TraceFormat::Std => TraceHeader::default(),
+        TraceFormat::RapidBin => {
+            // the header of a RapidBin trace precedes the events, so the trace is counted in a first pass
+            if arguments.input == STDIN_PATH {
+                return Err(AnalyzerError::from(io::Error::new(
+                    ErrorKind::Unsupported,
+                    "the standard input can't be read twice to count the events for the RapidBin header",
+                )));
+            }
 
-        let event = match parse_event(tokens) {
-            Ok(event) => event,
-            Err(err) => return errors.push(AnalyzerError::from(err)),
-        };
+            let trace_reader =
+                TraceReader::open_with(&arguments.input, arguments.from, arguments.normalize)?;
+            let report = run_analyses(trace_reader, vec![Box::new(Statistics::default())]);
 
-        match analyze_event(arguments, event, locks, row, graphviz, lock_dependencies) {
-            Ok(_) => {}
-            Err(error) => {
-                errors.push(error);
+            if let Some(err) = report.violations.into_iter().next() {
+                return Err(err);
             }
+
+            TraceHeader::try_from(&report.metadata.unwrap_or_default())?
         }
+    };
 
-        row += 1;
-    }
-}
+    let mut statistics: Box<dyn Analysis> = Box::new(Statistics::default());
 
-const NUM_THREADS_MASK: i16 = 0x7FFF;
-const NUM_LOCKS_MASK: i32 = 0x7FFFFFFF;
-const NUM_VARS_MASK: i32 = 0x7FFFFFFF;
-const NUM_EVENTS_MASK: i64 = 0x7FFFFFFFFFFFFFFF;
-
-const NUM_THREAD_BITS: i16 = 10;
-const THREAD_BITS_OFFSET: i16 = 0;
-const NUM_OPERATION_BITS: i16 = 4;
-const OPERATION_BITS_OFFSET: i16 = THREAD_BITS_OFFSET;
-const NUM_OPERAND_BITS: i16 = 34;
-const OPERAND_BITS_OFFSET: i16 = NUM_THREAD_BITS + NUM_OPERATION_BITS;
-const NUM_LOCATION_BITS: i16 = 15;
-const LOCATION_BITS_OFFSET: i16 = NUM_THREAD_BITS + NUM_OPERATION_BITS + NUM_OPERAND_BITS;
-
-const THREAD_MASK: i64 = ((1 << NUM_THREAD_BITS) - 1) << THREAD_BITS_OFFSET;
-const OPERATION_MASK: i64 = ((1 << NUM_OPERATION_BITS) - 1) << OPERATION_BITS_OFFSET;
-const OPERAND_MASK: i64 = ((1 << NUM_OPERAND_BITS) - 1) << OPERATION_BITS_OFFSET;
-const LOCATION_MASK: i64 = ((1 << NUM_LOCATION_BITS) - 1) << LOCATION_BITS_OFFSET;
-
-/// Parses a trace written in RapidBin format
-///
-/// # Arguments
-///
-/// * `arguments`:  the command line arguments
-/// * `trace_reader`:  the reader containing the contents of a RapidBin file
-/// * `errors`: a vector containing the errors the analyzer encountered
-/// * `locks`: a vector containing all locks of the trace
-/// * `row`: the current row of the trace
-/// * `graphviz`: a hashset containing edges for the GraphViz representation of a trace
-/// * `lock_dependencies`: a vector containing the lock dependencies of a trace
-///
-/// returns: () unit
-///
-fn analyze_rapid_trace(
-    arguments: &Arguments,
-    trace_reader: &mut BufReader<File>,
-    errors: &mut Vec<AnalyzerError>,
-    locks: &mut HashMap<i64, Lock>,
-    mut row: usize,
-    graphviz: &mut HashSet<Edge>,
-    lock_dependencies: &mut Vec<LockDependency>,
-) {
-    parse_trace_header(trace_reader);
-
-    let mut event_buffer = [0u8; 8];
-
-    while trace_reader.read_exact(&mut event_buffer).is_ok() {
-        let event = match try_parse_event(event_buffer) {
-            None => continue,
-            Some(event) => event,
-        };
+    write_output(&arguments.output, |sink| {
+        let mut trace_writer =
+            TraceWriter::new(sink, to, &header).map_err(|err| err.at_output(&arguments.output))?;
+        let trace_reader =
+            TraceReader::open_with(&arguments.input, arguments.from, arguments.normalize)?;
 
-        match analyze_event(arguments, event, locks, row, graphviz, lock_dependencies) {
-            Ok(_) => {}
-            Err(error) => errors.push(error),
+        for (row, event) in (1..).zip(trace_reader) {
+            let event = event?;
+
+            statistics.on_event(row, &event);
+            trace_writer
+                .write_event(&event)
+                .map_err(|err| err.at_output(&arguments.output))?;
         }
 
-        row += 1;
-    }
+        trace_writer
+            .finish()
+            .map_err(|err| err.at_output(&arguments.output))?;
+
+        Ok(())
+    })?;
+
+    let mut metadata = statistics.finish().metadata.unwrap_or_default();
+    metadata.format = Some(to);
+
+    Ok(metadata)
 }
 
-/// Tries to parse the header of a RapidBin file which contains information about the amount of threads, locks, variables and events of a trace
+/// Determines the exit code of a run
 ///
 /// # Arguments
 ///
-/// * `trace_reader`: the reader containing the contents of RapidBin file
+/// * `report`: the results of the analyses
+/// * `fail_on`: the kinds of findings which make the run fail
 ///
-/// returns: () unit since it just consumes the buffered reader
+/// returns: i32 `EXIT_INPUT_ERROR` if the trace could not be read, `EXIT_OUTPUT_ERROR` if the results could not be
+/// written, otherwise `EXIT_VIOLATIONS` or `EXIT_FINDINGS` if such findings were selected and found, `EXIT_SUCCESS`
+/// otherwise
 ///
-fn parse_trace_header(trace_reader: &mut BufReader<File>) {
-    let mut short_buffer = [0u8; 2];
-    let mut integer_buffer = [0u8; 4];
-    let mut long_buffer = [0u8; 8];
+pub fn exit_code(report: &Report, fail_on: &[FindingKind]) -> i32 {
+    if report.violations.iter().any(AnalyzerError::is_input_error) {
+        return EXIT_INPUT_ERROR;
+    }
 
-    trace_reader.read_exact(&mut short_buffer).unwrap();
-    let num_threads = i16::from_be_bytes(short_buffer) & NUM_THREADS_MASK;
+    if report.violations.iter().any(AnalyzerError::is_output_error) {
+        return EXIT_OUTPUT_ERROR;
+    }
 
-    trace_reader.read_exact(&mut integer_buffer).unwrap();
-    let num_locks = i32::from_be_bytes(integer_buffer) & NUM_LOCKS_MASK;
+    if fail_on.contains(&FindingKind::Violations) && !report.violations.is_empty() {
+        return EXIT_VIOLATIONS;
+    }
 
-    trace_reader.read_exact(&mut integer_buffer).unwrap();
-    let num_variables = i32::from_be_bytes(integer_buffer) & NUM_VARS_MASK;
+    let findings = [
+        (FindingKind::Deadlocks, report.deadlocks.is_empty()),
+        (FindingKind::Races, report.races.is_empty()),
+        (FindingKind::Lockset, report.lockset_violations.is_empty()),
+        (
+            FindingKind::Atomicity,
+            report.atomicity_violations.is_empty(),
+        ),
+    ];
 
-    trace_reader.read_exact(&mut long_buffer).unwrap();
-    let num_events = i64::from_be_bytes(long_buffer) & NUM_EVENTS_MASK;
+    if findings
+        .iter()
+        .any(|(kind, is_empty)| fail_on.contains(kind) && !is_empty)
+    {
+        return EXIT_FINDINGS;
+    }
 
-    info!("NUM_THREADS: {}", num_threads);
-    info!("NUM_LOCKS: {}", num_locks);
-    info!("NUM_VARIABLES: {}", num_variables);
-    info!("NUM_EVENTS: {}", num_events);
+    EXIT_SUCCESS
 }
 
-/// Tries to parse an event in RapidBin format
+/// Selects the analysis passes requested by the command line arguments
 ///
 /// # Arguments
 ///
-/// * `event_buffer`: the buffer containing the bytes of a RapidBin event
+/// * `arguments`: the command line arguments
 ///
-/// returns: Option<Event> an event if it was successfully parsed, None otherwise
+/// returns: Vec<Box<dyn Analysis, Global>, Global> the passes to run over the trace
 ///
-fn try_parse_event(event_buffer: [u8; 8]) -> Option<Event> {
-    let raw_event = i64::from_be_bytes(event_buffer);
-
-    let thread_identifier = (raw_event & THREAD_MASK) >> THREAD_BITS_OFFSET;
-    let operation_id = (raw_event & OPERATION_MASK) >> OPERATION_BITS_OFFSET;
-    let operand_id = (raw_event & OPERAND_MASK) >> OPERAND_BITS_OFFSET;
-    let loc = (raw_event & LOCATION_MASK) >> LOCATION_BITS_OFFSET;
+fn analyses_of(arguments: &Arguments) -> Vec<Box<dyn Analysis>> {
+    let mut analyses: Vec<Box<dyn Analysis>> = vec![
+        Box::new(WellFormedness::new(
+            arguments.lock_semantics,
+            arguments.held_locks,
+        )),
+        Box::new(ForkJoin::default()),
+        Box::new(Statistics::default()),
+    ];
+
+    // RAPID marks the start and end of threads via `begin` and `end` as well, so their nesting is only checked on demand
+    if arguments.atomic_blocks {
+        analyses.push(Box::new(AtomicBlocks::default()));
+    }
 
-    let operation = match Operation::new(operation_id) {
-        None => return None,
-        Some(operation) => operation,
-    };
+    if arguments.graph {
+        analyses.push(Box::new(LockGraph::new(arguments.lock_semantics)));
+    }
 
-    let operand = Operand::new(&operation, operand_id);
+    if arguments.lock_dependencies || arguments.spd {
+        analyses.push(Box::new(LockDependencies::new(
+            arguments.lock_semantics,
+            arguments.spd,
+        )));
+    }
 
-    let event = Event {
-        thread_identifier,
-        operation,
-        operand,
-        loc,
-    };
+    if arguments.goodlock {
+        analyses.push(Box::new(Goodlock::new(arguments.lock_semantics)));
+    }
 
-    debug!("{:?}", event);
+    if arguments.requests {
+        analyses.push(Box::new(Requests::default()));
+    }
 
-    Some(event)
-}
+    if arguments.unwritten_reads {
+        analyses.push(Box::new(UnwrittenReads::default()));
+    }
 
-/// Analyzes a single event of a trace
-///
-/// # Arguments
-///
-/// * `arguments`: the command line arguments
-/// * `event`: the to be analyzed event
-/// * `locks`: a hashmap containing all locks of a trace
-/// * `line`: the current line of the trace
-/// * `graphviz`: a hashset containing edges for the GraphViz representation of a trace
-/// * `lock_dependencies`: a vector containing all lock dependencies of a trace
-///
-/// returns: Result<(), AnalyzerError> unit if the event doesn't violate well-formedness, an error otherwise
-///
-fn analyze_event(
-    arguments: &Arguments,
-    event: Event,
-    locks: &mut HashMap<i64, Lock>,
-    line: usize,
-    graphviz: &mut HashSet<Edge>,
-    lock_dependencies: &mut Vec<LockDependency>,
-) -> Result<(), AnalyzerError> {
-    match event.operation {
-        Operation::Acquire => {
-            let lock_id = event.operand.id().unwrap();
-            let thread_owned_locks = locks_of_thread(event.thread_identifier, locks);
-
-            if &arguments.lock_dependencies == &true {
-                let acquired_locks = if thread_owned_locks.len() > 0 {
-                    thread_owned_locks.clone()
-                } else {
-                    HashSet::new()
-                };
-
-                let existing = lock_dependencies.iter().find(|dependency| {
-                    dependency.thread_id == event.thread_identifier
-                        && dependency.lock_id == lock_id
-                        && dependency.acquired_locks == thread_owned_locks
-                });
+    match arguments.races {
+        Some(RaceMode::Hb) => analyses.push(Box::new(HappensBefore::default())),
+        Some(RaceMode::Shb) => analyses.push(Box::new(HappensBefore::schedulable())),
+        Some(RaceMode::Wcp) => analyses.push(Box::new(WeakCausallyPrecedes::default())),
+        None => {}
+    }
 
-                if existing.is_none() {
-                    let lock_dependency = LockDependency {
-                        thread_id: event.thread_identifier,
-                        lock_id,
-                        acquired_locks,
-                        line,
-                    };
-
-                    lock_dependencies.push(lock_dependency);
-                }
-            }
-
-            if &arguments.graph == &true {
-                for owned_lock in thread_owned_locks {
-                    graphviz.insert(Edge {
-                        from: owned_lock,
-                        to: lock_id,
-                    });
-                }
-            }
-
-            if let Some(lock) = locks.get(&lock_id) {
-                if lock.locked && lock.owner.unwrap() != event.thread_identifier {
-                    return Err(AnalyzerError::RepeatedAcquisition {
-                        lock_id,
-                        thread_id: event.thread_identifier,
-                        owner_id: lock.owner.unwrap(),
-                        row: line,
-                    });
-                }
-            }
-
-            let lock = Lock {
-                owner: Some(event.thread_identifier),
-                locked: true,
-                row: line,
-            };
+    if arguments.lockset {
+        analyses.push(Box::new(Lockset::new(arguments.lock_semantics)));
+    }
 
-            locks.insert(lock_id, lock);
-            debug!(
-                "Thread 'T{}' acquired lock 'L{lock_id}' in line {line}",
-                event.thread_identifier
-            );
-        }
-        Operation::Release => {
-            let lock_id = event.operand.id().unwrap();
-
-            if &arguments.lock_dependencies == &true {
-                if let Some(lock_dependency) =
-                    lock_dependency_of_thread(event.thread_identifier, lock_dependencies)
-                {
-                    lock_dependency.clone().remove_lock(lock_id);
-                }
-            }
-
-            match locks.get(&lock_id) {
-                None => {
-                    return Err(AnalyzerError::ReleasedNonAcquiredLock {
-                        row: line,
-                        lock_id,
-                        thread_id: event.thread_identifier,
-                    });
-                }
-                Some(lock) => {
-                    if !lock.locked {
-                        return Err(AnalyzerError::RepeatedRelease {
-                            attempted: line,
-                            previous: lock.row,
-                            lock_id,
-                            thread_id: event.thread_identifier,
-                        });
-                    }
-
-                    if let Some(owner) = lock.owner {
-                        if owner != event.thread_identifier {
-                            return Err(AnalyzerError::ReleasedNonOwningLock {
-                                row: line,
-                                lock_id,
-                                thread_id: event.thread_identifier,
-                                owner,
-                            });
-                        }
-                    }
-
-                    let updated_lock = Lock {
-                        locked: false,
-                        owner: None,
-                        row: line,
-                    };
-
-                    locks.insert(lock_id, updated_lock);
-
-                    debug!(
-                        "Thread 'T{}' released lock 'L{lock_id}' in line {line}",
-                        event.thread_identifier
-                    );
-                }
-            }
-        }
-        // other operations are not needed to check well-formedness
-        _ => {}
+    if arguments.atomicity {
+        analyses.push(Box::new(Atomicity::default()));
     }
 
-    Ok(())
+    analyses
 }
 
-/// Returns all owned locks of a given thread
+/// Returns the path of a GraphViz representation
 ///
 /// # Arguments
 ///
-/// * `thread_id`: the id of the to be searched thread
-/// * `locks`: a vector containing all locks of a trace
+/// * `path`: the path given by the command line arguments
+/// * `input`: the path to the trace file
+/// * `suffix`: distinguishes the representations of the same trace
 ///
-/// returns: HashSet<i64> a HashSet containing all ids of the locks the thread owns
+/// returns: String the given path, otherwise a `.dot` file in the output directory named after the trace
 ///
-fn locks_of_thread(thread_id: i64, locks: &mut HashMap<i64, Lock>) -> HashSet<i64> {
-    let thread_owned_locks = locks
-        .iter()
-        .filter(|(_, lock)| lock.owner.is_some() && lock.owner.unwrap() == thread_id)
-        .map(|(id, _)| *id)
-        .collect();
+fn output_path(path: Option<&str>, input: &str, suffix: &str) -> String {
+    if let Some(path) = path {
+        return path.to_string();
+    }
 
-    thread_owned_locks
-}
+    let stem = Path::new(input)
+        .file_stem()
+        .and_then(|stem| stem.to_str())
+        .filter(|stem| *stem != STDIN_PATH)
+        .unwrap_or("trace");
 
-/// Returns the lock dependency of a given thread id
-///
-/// # Arguments
-///
-/// * `thread_id`: the id of the to be searched thread
-/// * `lock_dependencies`: a vector containing all lock dependencies of a trace
-///
-/// returns: Option<&LockDependency> a lock dependency if a thread with this id exists, otherwise None
-///
-fn lock_dependency_of_thread(
-    thread_id: i64,
-    lock_dependencies: &mut Vec<LockDependency>,
-) -> Option<&LockDependency> {
-    lock_dependencies
-        .iter()
-        .find(|dependency| dependency.thread_id == thread_id)
+    format!("{OUTPUT_DIRECTORY}/{stem}_{suffix}.dot")
 }
 
-/// Investigates a given directed graph if it contains a cycle via depth first search
+/// Writes an output file or the standard output
 ///
 /// # Arguments
 ///
-/// * `graph`: the graph to investigate
+/// * `path`: the path of the output file, `-` for the standard output
+/// * `write`: writes the contents to the given sink
 ///
-/// returns: usize the amount of detected cycles
+/// returns: Result<(), AnalyzerError> unit if the contents were written, an error otherwise
 ///
-pub fn validate_dependency_graph(graph: Graph) -> usize {
-    let mut visited = HashMap::<i64, bool>::new();
-    let mut recursion_stack = HashMap::<i64, bool>::new();
-
-    let mut found_deadlocks = 0;
-
-    for node in graph.keys() {
-        if (visited.get(&node).is_none()
-            || visited.get(&node).is_some() && visited.get(&node).unwrap() == &false)
-            && contains_cycle(&graph, *node, &mut visited, &mut recursion_stack)
-        {
-            found_deadlocks += 1;
-        }
+fn write_output<F>(path: &str, write: F) -> Result<(), AnalyzerError>
+where
+    F: FnOnce(&mut dyn Write) -> Result<(), AnalyzerError>,
+{
+    if path == "-" {
+        return write(&mut io::stdout().lock());
     }
 
-    found_deadlocks
-}
+    let output_error = |err: io::Error| AnalyzerError::from(err).at_output(path);
+    let file_path = Path::new(path);
 
-/// Helper function to detect a cycle in a given graph
-///
-/// # Arguments
-///
-/// * `graph`: the graph to investigate
-/// * `node`: the current node of the graph to check
-/// * `visited`: a Hashmap containing the already visited nodes
-/// * `recursion_stack`: a Hashmap keeping track of the current recursion stack
-///
-/// returns: bool true if the current node forms a cycle in the given graph
-///
-fn contains_cycle(
-    graph: &Graph,
-    node: i64,
-    visited: &mut HashMap<i64, bool>,
-    recursion_stack: &mut HashMap<i64, bool>,
-) -> bool {
-    visited.insert(node, true);
-    recursion_stack.insert(node, true);
-
-    if let Some(node) = graph.get(&node) {
-        for child in node.clone() {
-            if visited.get(&child).is_none()
-                && contains_cycle(graph, child, visited, recursion_stack)
-            {
-                return true;
-            } else if recursion_stack.get(&child).is_some()
-                && recursion_stack.get(&child).unwrap() == &true
-            {
-                return true;
-            }
-        }
+    if let Some(directory) = file_path
+        .parent()
+        .filter(|parent| !parent.as_os_str().is_empty())
+    {
+        fs::create_dir_all(directory).map_err(output_error)?;
     }
 
-    recursion_stack.insert(node, false);
+    // concurrent runs never observe partially written files since a rename replaces the file atomically
+    let mut temporary = file_path.as_os_str().to_owned();
+    temporary.push(format!(".{}.tmp", process::id()));
+
+    let mut file = BufWriter::new(File::create(&temporary).map_err(output_error)?);
 
-    false
+    write(&mut file)
+        .and_then(|_| file.flush().map_err(output_error))
+        .and_then(|_| fs::rename(&temporary, file_path).map_err(output_error))
+        .inspect_err(|_| {
+            let _ = fs::remove_file(&temporary);
+        })
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::analyzer::analyze_trace;
-    use crate::arguments::Arguments;
+    use crate::analysis::{Access, DeadlockKind, LockAcquisition, Transaction};
+    use crate::analyzer::{
+        analyze, analyze_trace, convert, exit_code, EXIT_FINDINGS, EXIT_INPUT_ERROR,
+        EXIT_OUTPUT_ERROR, EXIT_SUCCESS, EXIT_VIOLATIONS,
+    };
+    use crate::arguments::{
+        Arguments, ConvertArguments, FindingKind, LockSemantics, OutputFormat, RaceMode,
+    };
     use crate::error::AnalyzerError;
+    use crate::parser::Operation;
+    use crate::reader::{TraceFormat, TraceReader};
 
     #[test]
     fn succeed_when_analyzing_valid_trace() -> Result<(), AnalyzerError> {
@@ -636,6 +391,30 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn succeed_when_analyzing_rapid_benchmarks() -> Result<(), AnalyzerError> {
+        let benchmarks = [
+            "input/Account.data",
+            "input/Bensalem.data",
+            "input/Dbcp1.data",
+            "input/DiningPhil.data",
+            "input/Transfer.data",
+        ];
+
+        for benchmark in benchmarks {
+            // arrange
+            let arguments = Arguments::new(benchmark, false, false, false, false);
+
+            // act
+            let result = analyze_trace(&arguments);
+
+            // assert
+            assert!(result.is_ok(), "{benchmark}: {result:?}");
+        }
+
+        Ok(())
+    }
+
     #[test]
     fn fail_when_acquire_lock_repeatedly() -> Result<(), AnalyzerError> {
         // arrange
@@ -771,4 +550,1249 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn detect_race_via_happens_before() -> Result<(), AnalyzerError> {
+        // arrange
+        let mut arguments = Arguments::new("test/data_race.std", true, false, false, false);
+        arguments.races = Some(RaceMode::Hb);
+
+        // act
+        let report = analyze(&arguments);
+
+        // assert
+        assert!(report.violations.is_empty());
+        assert_eq!(report.races.len(), 1);
+        assert_eq!(report.races[0].location, 2);
+        assert_eq!(
+            report.races[0].earlier,
+            Access {
+                row: 10,
+                thread_id: 1,
+                operation: Operation::Write,
+                loc: 10,
+            }
+        );
+        assert_eq!(
+            report.races[0].later,
+            Access {
+                row: 11,
+                thread_id: 2,
+                operation: Operation::Read,
+                loc: 11,
+            }
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn detect_empty_lockset() -> Result<(), AnalyzerError> {
+        // arrange
+        let mut arguments = Arguments::new("test/lockset_violation.std", true, false, false, false);
+        arguments.lockset = true;
+
+        // act
+        let report = analyze(&arguments);
+
+        // assert
+        assert!(report.violations.is_empty());
+        assert_eq!(report.lockset_violations.len(), 1);
+        assert_eq!(report.lockset_violations[0].location, 1);
+        assert_eq!(
+            report.lockset_violations[0].access,
+            Access {
+                row: 8,
+                thread_id: 2,
+                operation: Operation::Read,
+                loc: 8,
+            }
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn fail_when_fork_started_thread() -> Result<(), AnalyzerError> {
+        // arrange
+        let arguments = Arguments::new("test/fork_started_thread.std", true, false, false, false);
+
+        // act
+        let errors = analyze_trace(&arguments).unwrap_err();
+
+        // assert
+        assert_eq!(errors.len(), 1);
+        assert!(match errors[0] {
+            AnalyzerError::ForkedStartedThread {
+                row,
+                thread_id,
+                child_id,
+                first_event,
+            } => {
+                assert_eq!(row, 3);
+                assert_eq!(thread_id, 1);
+                assert_eq!(child_id, 2);
+                assert_eq!(first_event, 2);
+
+                true
+            }
+            _ => false,
+        });
+
+        Ok(())
+    }
+
+    #[test]
+    fn succeed_when_thread_begins_before_fork() -> Result<(), AnalyzerError> {
+        // arrange
+        let arguments = Arguments::new("test/begin_before_fork.std", true, false, false, false);
+
+        // act
+        let result = analyze_trace(&arguments);
+
+        // assert
+        assert!(result.is_ok());
+
+        Ok(())
+    }
+
+    #[test]
+    fn fail_when_fork_thread_repeatedly() -> Result<(), AnalyzerError> {
+        // arrange
+        let arguments = Arguments::new("test/repeated_fork.std", true, false, false, false);
+
+        // act
+        let errors = analyze_trace(&arguments).unwrap_err();
+
+        // assert
+        assert_eq!(errors.len(), 1);
+        assert!(match errors[0] {
+            AnalyzerError::RepeatedFork {
+                attempted,
+                previous,
+                thread_id,
+                child_id,
+            } => {
+                assert_eq!(attempted, 3);
+                assert_eq!(previous, 1);
+                assert_eq!(thread_id, 1);
+                assert_eq!(child_id, 2);
+
+                true
+            }
+            _ => false,
+        });
+
+        Ok(())
+    }
+
+    #[test]
+    fn fail_when_event_after_join() -> Result<(), AnalyzerError> {
+        // arrange
+        let arguments = Arguments::new("test/event_after_join.std", true, false, false, false);
+
+        // act
+        let errors = analyze_trace(&arguments).unwrap_err();
+
+        // assert
+        assert_eq!(errors.len(), 1);
+        assert!(match errors[0] {
+            AnalyzerError::EventAfterJoin {
+                row,
+                thread_id,
+                joined,
+            } => {
+                assert_eq!(row, 4);
+                assert_eq!(thread_id, 2);
+                assert_eq!(joined, 3);
+
+                true
+            }
+            _ => false,
+        });
+
+        Ok(())
+    }
+
+    #[test]
+    fn fail_when_join_non_forked_thread() -> Result<(), AnalyzerError> {
+        // arrange
+        let arguments =
+            Arguments::new("test/join_non_forked_thread.std", true, false, false, false);
+
+        // act
+        let errors = analyze_trace(&arguments).unwrap_err();
+
+        // assert
+        assert_eq!(errors.len(), 1);
+        assert!(match errors[0] {
+            AnalyzerError::JoinedNonForkedThread {
+                row,
+                thread_id,
+                child_id,
+            } => {
+                assert_eq!(row, 3);
+                assert_eq!(thread_id, 1);
+                assert_eq!(child_id, 2);
+
+                true
+            }
+            _ => false,
+        });
+
+        Ok(())
+    }
+
+    #[test]
+    fn fail_when_join_thread_repeatedly() -> Result<(), AnalyzerError> {
+        // arrange
+        let arguments = Arguments::new("test/repeated_join.std", true, false, false, false);
+
+        // act
+        let errors = analyze_trace(&arguments).unwrap_err();
+
+        // assert
+        assert_eq!(errors.len(), 1);
+        assert!(match errors[0] {
+            AnalyzerError::RepeatedJoin {
+                attempted,
+                previous,
+                thread_id,
+                child_id,
+            } => {
+                assert_eq!(attempted, 4);
+                assert_eq!(previous, 3);
+                assert_eq!(thread_id, 1);
+                assert_eq!(child_id, 2);
+
+                true
+            }
+            _ => false,
+        });
+
+        Ok(())
+    }
+
+    #[test]
+    fn fail_when_fork_and_join_itself() -> Result<(), AnalyzerError> {
+        // arrange
+        let arguments = Arguments::new("test/self_fork_join.std", true, false, false, false);
+
+        // act
+        let errors = analyze_trace(&arguments).unwrap_err();
+
+        // assert
+        assert_eq!(errors.len(), 2);
+        assert!(matches!(
+            errors[0],
+            AnalyzerError::ForkedItself {
+                row: 1,
+                thread_id: 1
+            }
+        ));
+        assert!(matches!(
+            errors[1],
+            AnalyzerError::JoinedItself {
+                row: 2,
+                thread_id: 1
+            }
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn fail_when_read_from_unwritten_memory() -> Result<(), AnalyzerError> {
+        // arrange
+        let mut arguments = Arguments::new(
+            "test/read_from_unwritten_memory.std",
+            true,
+            false,
+            false,
+            false,
+        );
+        arguments.unwritten_reads = true;
+
+        // act
+        let errors = analyze_trace(&arguments).unwrap_err();
+
+        // assert
+        assert_eq!(errors.len(), 2); // the trace also releases a non-acquired lock
+        assert!(errors.iter().any(|error| match *error {
+            AnalyzerError::ReadFromUnwrittenMemory {
+                row,
+                thread_id,
+                location,
+            } => {
+                assert_eq!(row, 1);
+                assert_eq!(thread_id, 6);
+                assert_eq!(location, 4294967298);
+
+                true
+            }
+            _ => false,
+        }));
+
+        Ok(())
+    }
+
+    #[test]
+    fn fail_when_request_and_acquisition_do_not_match() -> Result<(), AnalyzerError> {
+        // arrange
+        let mut arguments = Arguments::new("test/request_pairing.std", true, false, false, false);
+        arguments.requests = true;
+
+        // act
+        let errors = analyze_trace(&arguments).unwrap_err();
+
+        // assert
+        assert_eq!(errors.len(), 3);
+        assert!(matches!(
+            errors[0],
+            AnalyzerError::AcquiredWithoutRequest {
+                row: 4,
+                lock_id: 1,
+                thread_id: 2
+            }
+        ));
+        assert!(matches!(
+            errors[1],
+            AnalyzerError::DanglingRequest {
+                row: 6,
+                lock_id: 2,
+                thread_id: 2
+            }
+        ));
+        assert!(matches!(
+            errors[2],
+            AnalyzerError::DanglingRequest {
+                row: 8,
+                lock_id: 3,
+                thread_id: 1
+            }
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn fail_when_acquisitions_precede_first_request() -> Result<(), AnalyzerError> {
+        // arrange
+        let mut arguments = Arguments::new("test/late_request.std", true, false, false, false);
+        arguments.requests = true;
+
+        // act
+        let errors = analyze_trace(&arguments).unwrap_err();
+
+        // assert
+        assert_eq!(errors.len(), 3);
+        assert!(matches!(
+            errors[0],
+            AnalyzerError::AcquiredWithoutRequest {
+                row: 1,
+                lock_id: 1,
+                thread_id: 1
+            }
+        ));
+        assert!(matches!(
+            errors[1],
+            AnalyzerError::AcquiredWithoutRequest {
+                row: 3,
+                lock_id: 1,
+                thread_id: 1
+            }
+        ));
+        assert!(matches!(
+            errors[2],
+            AnalyzerError::AcquiredWithoutRequest {
+                row: 5,
+                lock_id: 2,
+                thread_id: 2
+            }
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn fail_when_acquire_reentrant_lock_before_outermost_release() -> Result<(), AnalyzerError> {
+        // arrange
+        let arguments = Arguments::new("test/reentrant_lock.std", true, false, false, false);
+
+        // act
+        let errors = analyze_trace(&arguments).unwrap_err();
+
+        // assert
+        assert_eq!(errors.len(), 1);
+        assert!(matches!(
+            errors[0],
+            AnalyzerError::RepeatedAcquisition {
+                lock_id: 1,
+                thread_id: 2,
+                owner_id: 1,
+                row: 4
+            }
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn fail_when_reacquire_non_reentrant_lock() -> Result<(), AnalyzerError> {
+        // arrange
+        let mut arguments = Arguments::new("test/reentrant_lock.std", true, false, false, false);
+        arguments.lock_semantics = LockSemantics::NonReentrant;
+
+        // act
+        let errors = analyze_trace(&arguments).unwrap_err();
+
+        // assert
+        assert_eq!(errors.len(), 2);
+        assert!(match errors[0] {
+            AnalyzerError::ReacquiredOwnedLock {
+                row,
+                lock_id,
+                thread_id,
+                previous,
+            } => {
+                assert_eq!(row, 2);
+                assert_eq!(lock_id, 1);
+                assert_eq!(thread_id, 1);
+                assert_eq!(previous, 1);
+
+                true
+            }
+            _ => false,
+        });
+        assert!(matches!(
+            errors[1],
+            AnalyzerError::ReleasedNonOwningLock {
+                row: 5,
+                lock_id: 1,
+                thread_id: 1,
+                owner: 2
+            }
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn fail_when_join_thread_holding_lock() -> Result<(), AnalyzerError> {
+        // arrange
+        let arguments = Arguments::new("test/held_locks.std", true, false, false, false);
+
+        // act
+        let errors = analyze_trace(&arguments).unwrap_err();
+
+        // assert
+        assert_eq!(errors.len(), 1);
+        assert!(match errors[0] {
+            AnalyzerError::JoinedThreadHoldsLock {
+                row,
+                thread_id,
+                child_id,
+                lock_id,
+                acquired,
+            } => {
+                assert_eq!(row, 3);
+                assert_eq!(thread_id, 1);
+                assert_eq!(child_id, 2);
+                assert_eq!(lock_id, 1);
+                assert_eq!(acquired, 2);
+
+                true
+            }
+            _ => false,
+        });
+
+        Ok(())
+    }
+
+    #[test]
+    fn fail_when_locks_held_at_end_of_trace() -> Result<(), AnalyzerError> {
+        // arrange
+        let mut arguments = Arguments::new("test/held_locks.std", true, false, false, false);
+        arguments.held_locks = true;
+
+        // act
+        let errors = analyze_trace(&arguments).unwrap_err();
+
+        // assert
+        assert_eq!(errors.len(), 3);
+        assert!(matches!(
+            errors[1],
+            AnalyzerError::LockHeldAtEnd {
+                lock_id: 1,
+                owner: 2,
+                row: 2
+            }
+        ));
+        assert!(matches!(
+            errors[2],
+            AnalyzerError::LockHeldAtEnd {
+                lock_id: 3,
+                owner: 1,
+                row: 4
+            }
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn fail_when_atomic_blocks_are_not_nested() -> Result<(), AnalyzerError> {
+        // arrange
+        let mut arguments = Arguments::new("test/atomic_blocks.std", true, false, false, false);
+        arguments.atomic_blocks = true;
+
+        // act
+        let errors = analyze_trace(&arguments).unwrap_err();
+
+        // assert
+        assert_eq!(errors.len(), 2);
+        assert!(matches!(
+            errors[0],
+            AnalyzerError::UnmatchedEnd {
+                row: 7,
+                thread_id: 2
+            }
+        ));
+        assert!(matches!(
+            errors[1],
+            AnalyzerError::UnclosedBegin {
+                row: 8,
+                thread_id: 2
+            }
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn detect_non_serializable_atomic_block() -> Result<(), AnalyzerError> {
+        // arrange
+        let mut arguments =
+            Arguments::new("test/atomicity_violation.std", true, false, false, false);
+        arguments.atomicity = true;
+
+        // act
+        let report = analyze(&arguments);
+
+        // assert
+        assert!(report.violations.is_empty());
+        assert_eq!(report.atomicity_violations.len(), 1);
+        assert_eq!(
+            report.atomicity_violations[0].transactions,
+            vec![
+                Transaction {
+                    thread_id: 1,
+                    begin: 1,
+                    end: Some(5),
+                },
+                Transaction {
+                    thread_id: 2,
+                    begin: 3,
+                    end: Some(3),
+                },
+            ]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn detect_deadlock_via_lock_dependencies() -> Result<(), AnalyzerError> {
+        // arrange
+        let arguments = Arguments::new("test/deadlock.std", true, false, true, false);
+
+        // act
+        let report = analyze(&arguments);
+
+        // assert
+        assert!(report.violations.is_empty());
+        assert_eq!(report.deadlocks.len(), 1);
+        assert_eq!(
+            report.deadlocks[0].acquisitions,
+            vec![
+                LockAcquisition {
+                    thread_id: 1,
+                    lock_id: 2,
+                    held_locks: vec![1],
+                    row: 3,
+                },
+                LockAcquisition {
+                    thread_id: 2,
+                    lock_id: 1,
+                    held_locks: vec![2],
+                    row: 7,
+                },
+            ]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn detect_race_via_weak_causally_precedes() -> Result<(), AnalyzerError> {
+        // arrange
+        let mut arguments = Arguments::new("test/predictable_race.std", true, false, false, false);
+
+        // act
+        arguments.races = Some(RaceMode::Hb);
+        let happens_before = analyze(&arguments);
+
+        arguments.races = Some(RaceMode::Wcp);
+        let weak_causally_precedes = analyze(&arguments);
+
+        // assert
+        assert!(happens_before.races.is_empty());
+        assert_eq!(weak_causally_precedes.races.len(), 1);
+        assert_eq!(weak_causally_precedes.races[0].earlier.row, 1);
+        assert_eq!(weak_causally_precedes.races[0].later.row, 6);
+
+        Ok(())
+    }
+
+    #[test]
+    fn ignore_race_ordered_by_fork_via_weak_causally_precedes() -> Result<(), AnalyzerError> {
+        // arrange
+        let mut arguments =
+            Arguments::new("test/wcp_unrelated_section.std", false, false, false, false);
+        arguments.races = Some(RaceMode::Wcp);
+
+        // act
+        let report = analyze(&arguments);
+
+        // assert
+        assert!(report.violations.is_empty());
+        assert!(report.races.is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn ignore_race_ordered_by_read_via_schedulable_happens_before() -> Result<(), AnalyzerError> {
+        // arrange
+        let mut arguments = Arguments::new("test/read_from_race.std", true, false, false, false);
+
+        // act
+        arguments.races = Some(RaceMode::Hb);
+        let happens_before = analyze(&arguments);
+
+        arguments.races = Some(RaceMode::Shb);
+        let schedulable_happens_before = analyze(&arguments);
+
+        // assert
+        assert_eq!(happens_before.races.len(), 2);
+        assert_eq!(schedulable_happens_before.races.len(), 1);
+        assert_eq!(schedulable_happens_before.races[0].location, 2);
+
+        Ok(())
+    }
+
+    #[test]
+    fn succeed_when_serializing_report_to_json() -> Result<(), AnalyzerError> {
+        // arrange
+        let arguments = Arguments::new("test/repeated_lock_release.std", true, false, false, false);
+
+        // act
+        let report = analyze(&arguments);
+        let document = serde_json::from_str::<serde_json::Value>(&report.to_json()).unwrap();
+
+        // assert
+        assert_eq!(document["metadata"]["format"], "std");
+        assert_eq!(document["metadata"]["threads"], 2);
+        assert_eq!(document["metadata"]["events"], 8);
+        assert_eq!(document["violations"][0]["kind"], "RepeatedRelease");
+        assert_eq!(document["violations"][0]["attempted"], 8);
+        assert!(document["races"].as_array().unwrap().is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn succeed_when_exit_code_reflects_findings() -> Result<(), AnalyzerError> {
+        // arrange
+        let valid = Arguments::new("test/deadlock.std", true, false, false, false);
+        let violations =
+            Arguments::new("test/repeated_lock_release.std", true, false, false, false);
+        let missing = Arguments::new("test/missing.std", true, false, false, false);
+        let mut deadlocks = Arguments::new("test/deadlock.std", true, false, true, false);
+
+        // act & assert
+        assert_eq!(exit_code(&analyze(&valid), &valid.fail_on), EXIT_SUCCESS);
+        assert_eq!(
+            exit_code(&analyze(&violations), &violations.fail_on),
+            EXIT_VIOLATIONS
+        );
+        assert_eq!(
+            exit_code(&analyze(&missing), &missing.fail_on),
+            EXIT_INPUT_ERROR
+        );
+        assert_eq!(
+            exit_code(&analyze(&deadlocks), &deadlocks.fail_on),
+            EXIT_FINDINGS
+        );
+
+        deadlocks.fail_on = vec![FindingKind::Violations, FindingKind::Races];
+        assert_eq!(
+            exit_code(&analyze(&deadlocks), &deadlocks.fail_on),
+            EXIT_SUCCESS
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn succeed_when_writing_graph_to_given_path() -> Result<(), AnalyzerError> {
+        // arrange
+        let path = std::env::temp_dir().join(format!("trace_analyzer_{}.dot", std::process::id()));
+        let mut arguments = Arguments::new("test/deadlock.std", true, true, false, false);
+        arguments.graph_out = Some(path.to_string_lossy().to_string());
+
+        // act
+        let report = analyze(&arguments);
+        let graphviz = std::fs::read_to_string(&path)?;
+        std::fs::remove_file(&path)?;
+
+        // assert
+        assert!(report.violations.is_empty());
+        assert!(graphviz.starts_with("digraph G {"));
+        assert!(graphviz.contains("L1 -> L2"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn ignore_reacquisition_of_reentrant_lock_in_graph() -> Result<(), AnalyzerError> {
+        // arrange
+        let path = std::env::temp_dir().join(format!(
+            "trace_analyzer_{}_reentrant.dot",
+            std::process::id()
+        ));
+        let mut arguments = Arguments::new("test/reentrant_lock.std", true, true, false, false);
+        arguments.graph_out = Some(path.to_string_lossy().to_string());
+
+        // act
+        analyze(&arguments);
+        let graphviz = std::fs::read_to_string(&path)?;
+        std::fs::remove_file(&path)?;
+
+        // assert
+        assert!(!graphviz.contains("L1 -> L1"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn fail_when_writing_graph_to_invalid_path() -> Result<(), AnalyzerError> {
+        // arrange
+        let mut arguments = Arguments::new("test/deadlock.std", true, false, true, false);
+        arguments.deps_out = Some("Cargo.toml/threads.dot".to_string());
+
+        // act
+        let report = analyze(&arguments);
+
+        // assert
+        assert_eq!(report.violations.len(), 1);
+        assert!(matches!(
+            report.violations[0],
+            AnalyzerError::OutputError { .. }
+        ));
+        assert_eq!(exit_code(&report, &arguments.fail_on), EXIT_OUTPUT_ERROR);
+
+        Ok(())
+    }
+
+    #[test]
+    fn fail_when_writing_graph_to_stdout_of_json_report() -> Result<(), AnalyzerError> {
+        // arrange
+        let mut arguments = Arguments::new("test/deadlock.std", true, true, false, false);
+        arguments.graph_out = Some("-".to_string());
+        arguments.format = OutputFormat::Json;
+
+        // act
+        let report = analyze(&arguments);
+
+        // assert
+        assert_eq!(report.violations.len(), 1);
+        assert!(matches!(
+            &report.violations[0],
+            AnalyzerError::OutputError { path, .. } if path == "-"
+        ));
+        assert_eq!(exit_code(&report, &arguments.fail_on), EXIT_OUTPUT_ERROR);
+
+        Ok(())
+    }
+
+    #[test]
+    fn detect_header_mismatch() -> Result<(), AnalyzerError> {
+        // arrange
+        let arguments = Arguments::new("test/truncated_events.data", false, false, false, false);
+
+        // act
+        let report = analyze(&arguments);
+
+        // assert
+        assert!(report.violations.iter().any(|violation| matches!(
+            violation,
+            AnalyzerError::HeaderMismatch {
+                field: "events",
+                declared: 39,
+                observed: 23
+            }
+        )));
+        assert_eq!(exit_code(&report, &FindingKind::all()), EXIT_VIOLATIONS);
+
+        Ok(())
+    }
+
+    #[test]
+    fn detect_only_unknown_operation_in_lenient_run() -> Result<(), AnalyzerError> {
+        // arrange
+        let arguments = Arguments::new("test/unknown_operation.data", false, false, false, false);
+
+        // act
+        let report = analyze(&arguments);
+
+        // assert
+        assert_eq!(report.violations.len(), 1);
+        assert!(matches!(
+            report.violations[0],
+            AnalyzerError::UnknownOperation { row: 3, code: 15 }
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn succeed_when_header_matches_trace() -> Result<(), AnalyzerError> {
+        // arrange
+        let arguments = Arguments::new("input/Deadlock.data", false, false, false, false);
+
+        // act
+        let report = analyze(&arguments);
+
+        // assert
+        assert!(!report
+            .violations
+            .iter()
+            .any(|violation| matches!(violation, AnalyzerError::HeaderMismatch { .. })));
+
+        Ok(())
+    }
+
+    #[test]
+    fn detect_every_malformed_line() -> Result<(), AnalyzerError> {
+        // arrange
+        let arguments = Arguments::new("test/malformed_lines.std", false, false, false, false);
+
+        // act
+        let report = analyze(&arguments);
+
+        // assert
+        let mut rows = report
+            .violations
+            .iter()
+            .filter_map(AnalyzerError::row)
+            .collect::<Vec<_>>();
+        rows.sort();
+
+        assert_eq!(rows, vec![2, 4, 6]);
+        assert!(matches!(
+            report.violations[0],
+            AnalyzerError::MalformedEvent { row: 2, .. }
+        ));
+        assert!(report.violations.iter().any(|violation| matches!(
+            violation,
+            AnalyzerError::ReleasedNonOwningLock { row: 4, .. }
+        )));
+
+        Ok(())
+    }
+
+    #[test]
+    fn detect_every_truncated_line_when_normalizing() -> Result<(), AnalyzerError> {
+        // arrange
+        let arguments = Arguments::new("test/truncated_lines.std", true, false, false, false);
+
+        // act
+        let report = analyze(&arguments);
+
+        // assert
+        let mut rows = report
+            .violations
+            .iter()
+            .filter_map(AnalyzerError::row)
+            .collect::<Vec<_>>();
+        rows.sort();
+
+        assert_eq!(rows, vec![2, 4, 6]);
+        assert!(matches!(
+            report.violations[0],
+            AnalyzerError::MalformedEvent { row: 2, .. }
+        ));
+        assert!(report
+            .violations
+            .iter()
+            .any(|violation| matches!(violation, AnalyzerError::MalformedEvent { row: 6, .. })));
+
+        Ok(())
+    }
+
+    #[test]
+    fn fail_when_reading_malformed_line_strictly() -> Result<(), AnalyzerError> {
+        // arrange
+        let mut arguments = Arguments::new("test/malformed_lines.std", false, false, false, false);
+        arguments.strict = true;
+
+        // act
+        let report = analyze(&arguments);
+
+        // assert
+        assert_eq!(report.violations.len(), 1);
+        assert!(matches!(
+            report.violations[0],
+            AnalyzerError::MalformedEvent { row: 2, .. }
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn succeed_when_converting_rapid_trace_to_std_and_back() -> Result<(), AnalyzerError> {
+        // arrange
+        let directory = std::env::temp_dir();
+        let std_path = directory.join(format!("trace_analyzer_{}.std", std::process::id()));
+        let data_path = directory.join(format!("trace_analyzer_{}.data", std::process::id()));
+
+        let to_std = ConvertArguments {
+            input: "input/Account.data".to_string(),
+            output: std_path.to_string_lossy().to_string(),
+            from: None,
+            to: None,
+            normalize: false,
+        };
+        let to_data = ConvertArguments {
+            input: to_std.output.clone(),
+            output: data_path.to_string_lossy().to_string(),
+            from: None,
+            to: None,
+            normalize: false,
+        };
+
+        // act
+        let metadata = convert(&to_std)?;
+        convert(&to_data)?;
+
+        let expected =
+            TraceReader::open("input/Account.data", false)?.collect::<Result<Vec<_>, _>>()?;
+        let converted = TraceReader::open(&data_path, false)?;
+        let header = converted.header().cloned().unwrap();
+        let events = converted.collect::<Result<Vec<_>, _>>()?;
+
+        std::fs::remove_file(&std_path)?;
+        std::fs::remove_file(&data_path)?;
+
+        // assert
+        assert_eq!(metadata.events, 706);
+        assert_eq!(events, expected);
+        assert_eq!(header.num_threads, 6);
+        assert_eq!(header.num_events, 706);
+
+        Ok(())
+    }
+
+    #[test]
+    fn succeed_when_converting_std_trace_to_rapid() -> Result<(), AnalyzerError> {
+        // arrange
+        let path = std::env::temp_dir().join(format!("trace_analyzer_{}.bin", std::process::id()));
+        let arguments = ConvertArguments {
+            input: "test/deadlock.std".to_string(),
+            output: path.to_string_lossy().to_string(),
+            from: None,
+            to: Some(TraceFormat::RapidBin),
+            normalize: true,
+        };
+
+        // act
+        convert(&arguments)?;
+
+        let expected =
+            TraceReader::open("test/deadlock.std", true)?.collect::<Result<Vec<_>, _>>()?;
+        let trace_reader = TraceReader::open_as(&path, TraceFormat::RapidBin, false)?;
+        let header = trace_reader.header().cloned().unwrap();
+        let events = trace_reader.collect::<Result<Vec<_>, _>>()?;
+
+        std::fs::remove_file(&path)?;
+
+        // assert
+        assert_eq!(events, expected);
+        assert_eq!(header.num_threads, 3);
+        assert_eq!(header.num_locks, 3);
+        assert_eq!(header.num_variables, 0);
+
+        Ok(())
+    }
+
+    #[test]
+    fn fail_when_converting_unencodable_event() -> Result<(), AnalyzerError> {
+        // arrange
+        let path = std::env::temp_dir().join(format!(
+            "trace_analyzer_{}_unencodable.data",
+            std::process::id()
+        ));
+        let arguments = ConvertArguments {
+            input: "test/unencodable_thread.std".to_string(),
+            output: path.to_string_lossy().to_string(),
+            from: None,
+            to: None,
+            normalize: false,
+        };
+
+        // act
+        let error = convert(&arguments).err().unwrap();
+
+        // assert
+        assert!(!path.exists());
+        assert!(matches!(
+            error,
+            AnalyzerError::UnencodableValue {
+                row: Some(2),
+                field: "thread",
+                value: 1024
+            }
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn fail_when_converting_malformed_trace() -> Result<(), AnalyzerError> {
+        for to in [TraceFormat::Std, TraceFormat::RapidBin] {
+            // arrange
+            let path = std::env::temp_dir().join(format!(
+                "trace_analyzer_{}_malformed_{to:?}",
+                std::process::id()
+            ));
+            let arguments = ConvertArguments {
+                input: "test/malformed_lines.std".to_string(),
+                output: path.to_string_lossy().to_string(),
+                from: None,
+                to: Some(to),
+                normalize: false,
+            };
+
+            // act
+            let error = convert(&arguments).err().unwrap();
+
+            // assert
+            assert!(!path.exists());
+            assert!(matches!(
+                error,
+                AnalyzerError::MalformedEvent { row: 2, .. }
+            ));
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn detect_predictable_deadlock() -> Result<(), AnalyzerError> {
+        // arrange
+        let mut arguments = Arguments::new("test/deadlock.std", true, false, false, false);
+        arguments.spd = true;
+
+        // act
+        let report = analyze(&arguments);
+
+        // assert
+        assert_eq!(report.deadlocks.len(), 1);
+        assert_eq!(report.deadlocks[0].kind, DeadlockKind::Predictable);
+        assert_eq!(
+            report.deadlocks[0]
+                .acquisitions
+                .iter()
+                .map(|acquisition| acquisition.row)
+                .collect::<Vec<_>>(),
+            vec![3, 7]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn detect_predictable_deadlock_after_overlapping_sections() -> Result<(), AnalyzerError> {
+        // arrange
+        let mut arguments =
+            Arguments::new("test/overlapping_sections.std", true, false, false, false);
+        arguments.spd = true;
+
+        // act
+        let report = analyze(&arguments);
+
+        // assert
+        assert!(!report.violations.is_empty());
+        assert_eq!(report.deadlocks.len(), 1);
+        assert_eq!(report.deadlocks[0].kind, DeadlockKind::Predictable);
+        assert_eq!(
+            report.deadlocks[0]
+                .acquisitions
+                .iter()
+                .map(|acquisition| acquisition.row)
+                .collect::<Vec<_>>(),
+            vec![7, 11]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn detect_potential_deadlock_ordered_by_read() -> Result<(), AnalyzerError> {
+        // arrange
+        let mut arguments = Arguments::new("test/ordered_deadlock.std", true, false, false, false);
+        arguments.spd = true;
+
+        // act
+        let report = analyze(&arguments);
+
+        // assert
+        assert!(report.violations.is_empty());
+        assert_eq!(report.deadlocks.len(), 1);
+        assert_eq!(report.deadlocks[0].kind, DeadlockKind::Potential);
+
+        Ok(())
+    }
+
+    #[test]
+    fn detect_deadlock_via_lock_order_graph() -> Result<(), AnalyzerError> {
+        // arrange
+        let mut arguments = Arguments::new("test/deadlock.std", true, false, false, false);
+        arguments.goodlock = true;
+
+        // act
+        let report = analyze(&arguments);
+
+        // assert
+        assert_eq!(report.deadlocks.len(), 1);
+        assert_eq!(
+            report.deadlocks[0].acquisitions,
+            vec![
+                LockAcquisition {
+                    thread_id: 1,
+                    lock_id: 2,
+                    held_locks: vec![1],
+                    row: 3,
+                },
+                LockAcquisition {
+                    thread_id: 2,
+                    lock_id: 1,
+                    held_locks: vec![2],
+                    row: 7,
+                },
+            ]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn detect_deadlock_once_via_lock_order_graph_and_spd() -> Result<(), AnalyzerError> {
+        // arrange
+        let mut arguments = Arguments::new("test/deadlock.std", true, false, false, false);
+        arguments.goodlock = true;
+        arguments.spd = true;
+
+        // act
+        let report = analyze(&arguments);
+
+        // assert
+        assert_eq!(report.deadlocks.len(), 1);
+        assert_eq!(report.deadlocks[0].kind, DeadlockKind::Predictable);
+
+        Ok(())
+    }
+
+    #[test]
+    fn detect_deadlock_in_benchmark_via_lock_order_graph() -> Result<(), AnalyzerError> {
+        // arrange
+        let mut arguments = Arguments::new("input/Bensalem.data", false, false, false, false);
+        arguments.goodlock = true;
+
+        // act
+        let report = analyze(&arguments);
+
+        // assert
+        assert!(report.violations.is_empty());
+        assert_eq!(report.deadlocks.len(), 1);
+        assert_eq!(
+            report.deadlocks[0].acquisitions,
+            vec![
+                LockAcquisition {
+                    thread_id: 2,
+                    lock_id: 2,
+                    held_locks: vec![1],
+                    row: 32,
+                },
+                LockAcquisition {
+                    thread_id: 1,
+                    lock_id: 1,
+                    held_locks: vec![2],
+                    row: 47,
+                },
+            ]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn ignore_single_threaded_cycle_in_lock_order_graph() -> Result<(), AnalyzerError> {
+        // arrange
+        let mut arguments =
+            Arguments::new("test/single_threaded_cycle.std", false, false, false, false);
+        arguments.goodlock = true;
+
+        // act
+        let report = analyze(&arguments);
+
+        // assert
+        assert!(report.violations.is_empty());
+        assert!(report.deadlocks.is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn ignore_gated_cycle_in_lock_order_graph() -> Result<(), AnalyzerError> {
+        // arrange
+        let mut arguments = Arguments::new("test/gated_cycle.std", false, false, false, false);
+        arguments.goodlock = true;
+
+        // act
+        let report = analyze(&arguments);
+
+        // assert
+        assert!(report.violations.is_empty());
+        assert!(report.deadlocks.is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn ignore_deadlock_guarded_by_gate_lock() -> Result<(), AnalyzerError> {
+        // arrange
+        let mut arguments = Arguments::new("test/guarded_deadlock.std", true, false, true, false);
+        arguments.goodlock = true;
+
+        // act
+        let report = analyze(&arguments);
+
+        // assert
+        assert!(report.violations.is_empty());
+        assert!(report.deadlocks.is_empty());
+
+        Ok(())
+    }
 }
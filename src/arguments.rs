@@ -1,27 +1,116 @@
-use clap::Parser;
+use crate::reader::TraceFormat;
+use clap::{Args, Parser, Subcommand, ValueEnum};
 
+/// The command line interface, analyzes a trace unless a subcommand is given
 #[derive(Parser, Debug)]
 #[command(author, version, about, long_about = None)]
+#[command(args_conflicts_with_subcommands = true, subcommand_negates_reqs = true)]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Option<Command>,
+    #[command(flatten)]
+    pub arguments: Option<Arguments>,
+}
+
+/// The subcommands besides the analysis of a trace
+#[derive(Subcommand, Debug)]
+pub enum Command {
+    /// Convert a trace between the STD and RapidBin format
+    Convert(ConvertArguments),
+}
+
+/// The command line arguments of the conversion between trace formats
+#[derive(Args, Debug)]
+pub struct ConvertArguments {
+    /// The filepath to the trace file, `-` for the standard input
+    #[arg(short, long)]
+    pub input: String,
+    /// The filepath of the converted trace, `-` for the standard output
+    #[arg(short, long)]
+    pub output: String,
+    /// The format of the input [default: detected by the content of the input]
+    #[arg(long, value_enum)]
+    pub from: Option<TraceFormat>,
+    /// The format of the output [default: determined by the extension of the output]
+    #[arg(long, value_enum)]
+    pub to: Option<TraceFormat>,
+    /// If the input file should be normalized
+    #[arg(short, long)]
+    pub normalize: bool,
+}
+
+/// The command line arguments of the analysis of a trace
+#[derive(Args, Debug)]
 pub struct Arguments {
-    /// The filepath to the trace file
+    /// The filepath to the trace file, `-` for the standard input
     #[arg(short, long)]
     pub input: String,
+    /// The format of the trace [default: detected by the content of the trace]
+    #[arg(long, value_enum)]
+    pub input_format: Option<TraceFormat>,
     /// If the input file should be normalized
     #[arg(short, long)]
     pub normalize: bool,
     /// If a graphical representation should be constructed (only suitable for small traces)
     #[arg(short, long)]
     pub graph: bool,
+    /// Abort at the first malformed event instead of reporting it and continuing with the remaining trace
+    #[arg(long)]
+    pub strict: bool,
     /// Analyze trace via lock dependencies (only suitable for small traces)
     #[arg(short, long)]
     pub lock_dependencies: bool,
+    /// Where the lock graph is written in GraphViz syntax, `-` for the standard output of the text format [default: output/<trace>_locks.dot]
+    #[arg(long)]
+    pub graph_out: Option<String>,
+    /// Where the thread dependencies are written in GraphViz syntax, `-` for the standard output of the text format [default: output/<trace>_threads.dot]
+    #[arg(long)]
+    pub deps_out: Option<String>,
+    /// If locks which are still held at the end of the trace should be reported as violations
+    #[arg(long)]
+    pub held_locks: bool,
+    /// If every acquisition should be checked for a preceding request of the same lock
+    #[arg(long)]
+    pub requests: bool,
+    /// If reads from never written memory locations should be reported as violations
+    #[arg(long)]
+    pub unwritten_reads: bool,
+    /// Check if a sync-preserving reordering reaches the deadlocks found via lock dependencies (SPD)
+    #[arg(long)]
+    pub spd: bool,
+    /// Predict deadlocks from cycles in the lock-order graph (Goodlock)
+    #[arg(long)]
+    pub goodlock: bool,
+    /// The semantics of a lock which is acquired by its owner again
+    #[arg(long, value_enum, default_value_t)]
+    pub lock_semantics: LockSemantics,
+    /// Detect data races with the given relation
+    #[arg(long, value_enum)]
+    pub races: Option<RaceMode>,
+    /// Check that every shared and modified memory location is protected by a common lock
+    #[arg(long)]
+    pub lockset: bool,
+    /// If the atomic blocks (`begin`/`end`) of every thread should be checked for proper nesting
+    #[arg(long)]
+    pub atomic_blocks: bool,
+    /// Check that atomic blocks are conflict-serializable
+    #[arg(long)]
+    pub atomicity: bool,
+    /// How the results of the analysis are reported
+    #[arg(long, value_enum, default_value_t)]
+    pub format: OutputFormat,
+    /// A file mapping the `loc` of events to `file|line` of the instrumented program, used by the SARIF output
+    #[arg(long)]
+    pub location_map: Option<String>,
+    /// The kinds of findings which make the run fail with a non-zero exit code
+    #[arg(long, value_enum, value_delimiter = ',', default_values_t = FindingKind::all())]
+    pub fail_on: Vec<FindingKind>,
     /// If each violation should be logged individually (only suitable for small traces)
     #[arg(short, long)]
     pub verbose: bool,
 }
 
 impl Arguments {
-    #[allow(dead_code)] // used when running tests
     pub fn new<S: Into<String>>(
         input: S,
         normalize: bool,
@@ -31,10 +120,87 @@ impl Arguments {
     ) -> Self {
         Self {
             input: input.into(),
+            input_format: None,
             normalize,
             graph,
+            strict: false,
             lock_dependencies,
+            graph_out: None,
+            deps_out: None,
+            held_locks: false,
+            requests: false,
+            lock_semantics: LockSemantics::default(),
+            unwritten_reads: false,
+            spd: false,
+            goodlock: false,
+            races: None,
+            lockset: false,
+            atomic_blocks: false,
+            atomicity: false,
+            format: OutputFormat::default(),
+            location_map: None,
+            fail_on: FindingKind::all(),
             verbose,
         }
     }
 }
+
+/// The formats in which the results of the analysis are reported
+#[derive(ValueEnum, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum OutputFormat {
+    /// Log messages
+    #[default]
+    Text,
+    /// A single JSON document on the standard output
+    Json,
+    /// A SARIF 2.1 log on the standard output
+    Sarif,
+}
+
+/// The kinds of findings which can make a run fail
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FindingKind {
+    /// Violations of the well-formedness of the trace
+    Violations,
+    /// Potential and predictable deadlocks
+    Deadlocks,
+    /// Data races
+    Races,
+    /// Memory locations with an empty lockset
+    Lockset,
+    /// Non-serializable atomic blocks
+    Atomicity,
+}
+
+impl FindingKind {
+    pub fn all() -> Vec<Self> {
+        vec![
+            FindingKind::Violations,
+            FindingKind::Deadlocks,
+            FindingKind::Races,
+            FindingKind::Lockset,
+            FindingKind::Atomicity,
+        ]
+    }
+}
+
+/// The relations used to detect data races
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RaceMode {
+    /// Happens-before
+    Hb,
+    /// Schedulable happens-before, additionally orders reads after the write they read from
+    Shb,
+    /// Weak-causally-precedes, only orders critical sections which contain conflicting accesses
+    Wcp,
+}
+
+/// The semantics of a lock which is acquired by its owner again
+#[derive(ValueEnum, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum LockSemantics {
+    /// Re-acquisitions are counted and the lock is only freed by the matching release
+    #[default]
+    Reentrant,
+    /// Re-acquisitions are violations
+    NonReentrant,
+}
@@ -1,8 +1,11 @@
 use peg::error::ParseError;
+use serde::ser::SerializeMap;
+use serde::{Serialize, Serializer};
 use std::fmt::{Debug, Display, Formatter};
 use std::io::Error as IOError;
 
-#[derive(Debug)]
+#[derive(Debug, Serialize)]
+#[serde(tag = "kind")]
 pub enum AnalyzerError {
     RepeatedAcquisition {
         lock_id: i64,
@@ -10,6 +13,12 @@ pub enum AnalyzerError {
         owner_id: i64,
         row: usize,
     },
+    ReacquiredOwnedLock {
+        row: usize,
+        lock_id: i64,
+        thread_id: i64,
+        previous: usize,
+    },
     RepeatedRelease {
         attempted: usize,
         previous: usize,
@@ -27,11 +36,204 @@ pub enum AnalyzerError {
         lock_id: i64,
         thread_id: i64,
     },
+    LockHeldAtEnd {
+        lock_id: i64,
+        owner: i64,
+        row: usize,
+    },
+    JoinedThreadHoldsLock {
+        row: usize,
+        thread_id: i64,
+        child_id: i64,
+        lock_id: i64,
+        acquired: usize,
+    },
+    ForkedItself {
+        row: usize,
+        thread_id: i64,
+    },
+    RepeatedFork {
+        attempted: usize,
+        previous: usize,
+        thread_id: i64,
+        child_id: i64,
+    },
+    ForkedStartedThread {
+        row: usize,
+        thread_id: i64,
+        child_id: i64,
+        first_event: usize,
+    },
+    JoinedItself {
+        row: usize,
+        thread_id: i64,
+    },
+    RepeatedJoin {
+        attempted: usize,
+        previous: usize,
+        thread_id: i64,
+        child_id: i64,
+    },
+    JoinedNonForkedThread {
+        row: usize,
+        thread_id: i64,
+        child_id: i64,
+    },
+    EventAfterJoin {
+        row: usize,
+        thread_id: i64,
+        joined: usize,
+    },
+    UnmatchedEnd {
+        row: usize,
+        thread_id: i64,
+    },
+    UnclosedBegin {
+        row: usize,
+        thread_id: i64,
+    },
+    AcquiredWithoutRequest {
+        row: usize,
+        lock_id: i64,
+        thread_id: i64,
+    },
+    DanglingRequest {
+        row: usize,
+        lock_id: i64,
+        thread_id: i64,
+    },
+    ReadFromUnwrittenMemory {
+        row: usize,
+        thread_id: i64,
+        location: i64,
+    },
     UnsupportedFileExtension,
+    InvalidLocationMapping {
+        row: usize,
+    },
+    UnencodableValue {
+        row: Option<usize>,
+        field: &'static str,
+        value: i64,
+    },
+    HeaderMismatch {
+        field: &'static str,
+        declared: i64,
+        observed: i64,
+    },
+    TruncatedHeader,
+    TrailingBytes {
+        count: usize,
+    },
+    UnknownOperation {
+        row: usize,
+        code: i64,
+    },
+    MalformedEvent {
+        row: usize,
+        error: Box<AnalyzerError>,
+    },
+    OutputError {
+        path: String,
+        #[serde(serialize_with = "serialize_message")]
+        error: IOError,
+    },
     // wrapped errors
-    IOError(IOError),
-    LexerError(LexerError),
-    ParserError(ParseError<usize>),
+    IOError(#[serde(serialize_with = "serialize_message")] IOError),
+    LexerError(#[serde(serialize_with = "serialize_message")] LexerError),
+    ParserError(#[serde(serialize_with = "serialize_message")] ParseError<usize>),
+}
+
+/// Serializes a wrapped error by its description since its fields are not meaningful outside of the analyzer
+fn serialize_message<E: Display, S: Serializer>(
+    error: &E,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    let mut map = serializer.serialize_map(Some(1))?;
+    map.serialize_entry("message", &error.to_string())?;
+    map.end()
+}
+
+impl AnalyzerError {
+    /// Returns if the error prevented the trace from being read, in contrast to a violation of its well-formedness
+    pub fn is_input_error(&self) -> bool {
+        matches!(
+            self,
+            AnalyzerError::UnsupportedFileExtension
+                | AnalyzerError::InvalidLocationMapping { .. }
+                | AnalyzerError::UnencodableValue { .. }
+                | AnalyzerError::TruncatedHeader
+                | AnalyzerError::TrailingBytes { .. }
+                | AnalyzerError::UnknownOperation { .. }
+                | AnalyzerError::MalformedEvent { .. }
+                | AnalyzerError::IOError(_)
+                | AnalyzerError::LexerError(_)
+                | AnalyzerError::ParserError(_)
+        )
+    }
+
+    /// Returns if the error prevented the results or a converted trace from being written
+    pub fn is_output_error(&self) -> bool {
+        matches!(self, AnalyzerError::OutputError { .. })
+    }
+
+    /// Attributes an I/O error to the output it occurred at, other errors are returned unchanged
+    ///
+    /// # Arguments
+    ///
+    /// * `path`: the path of the output, `-` for the standard output
+    ///
+    /// returns: AnalyzerError an `OutputError` if the error is an I/O error, the error itself otherwise
+    ///
+    pub fn at_output(self, path: &str) -> Self {
+        match self {
+            AnalyzerError::IOError(error) => AnalyzerError::OutputError {
+                path: path.to_string(),
+                error,
+            },
+            error => error,
+        }
+    }
+
+    /// Returns the row of the event which caused a violation
+    ///
+    /// returns: Option<usize> the row of the offending event, None if the error is not caused by an event
+    ///
+    pub fn row(&self) -> Option<usize> {
+        match self {
+            AnalyzerError::RepeatedAcquisition { row, .. }
+            | AnalyzerError::ReacquiredOwnedLock { row, .. }
+            | AnalyzerError::ReleasedNonOwningLock { row, .. }
+            | AnalyzerError::ReleasedNonAcquiredLock { row, .. }
+            | AnalyzerError::LockHeldAtEnd { row, .. }
+            | AnalyzerError::JoinedThreadHoldsLock { row, .. }
+            | AnalyzerError::ForkedItself { row, .. }
+            | AnalyzerError::ForkedStartedThread { row, .. }
+            | AnalyzerError::JoinedItself { row, .. }
+            | AnalyzerError::JoinedNonForkedThread { row, .. }
+            | AnalyzerError::EventAfterJoin { row, .. }
+            | AnalyzerError::UnmatchedEnd { row, .. }
+            | AnalyzerError::UnclosedBegin { row, .. }
+            | AnalyzerError::AcquiredWithoutRequest { row, .. }
+            | AnalyzerError::DanglingRequest { row, .. }
+            | AnalyzerError::ReadFromUnwrittenMemory { row, .. }
+            | AnalyzerError::UnknownOperation { row, .. }
+            | AnalyzerError::MalformedEvent { row, .. } => Some(*row),
+            AnalyzerError::RepeatedRelease { attempted, .. }
+            | AnalyzerError::RepeatedFork { attempted, .. }
+            | AnalyzerError::RepeatedJoin { attempted, .. } => Some(*attempted),
+            AnalyzerError::UnencodableValue { row, .. } => *row,
+            AnalyzerError::UnsupportedFileExtension
+            | AnalyzerError::InvalidLocationMapping { .. }
+            | AnalyzerError::HeaderMismatch { .. }
+            | AnalyzerError::TruncatedHeader
+            | AnalyzerError::TrailingBytes { .. }
+            | AnalyzerError::OutputError { .. }
+            | AnalyzerError::IOError(_)
+            | AnalyzerError::LexerError(_)
+            | AnalyzerError::ParserError(_) => None,
+        }
+    }
 }
 
 impl Display for AnalyzerError {
@@ -45,6 +247,14 @@ impl Display for AnalyzerError {
             } => {
                 format!("Thread 'T{thread_id}' tried to acquire the already acquired lock 'L{lock_id}' in row {row}. Current owner is {owner_id}")
             }
+            AnalyzerError::ReacquiredOwnedLock {
+                row,
+                lock_id,
+                thread_id,
+                previous,
+            } => {
+                format!("Thread 'T{thread_id}' tried to re-acquire its own non-reentrant lock 'L{lock_id}' in row {row}. Lock was acquired in row {previous}")
+            }
             AnalyzerError::RepeatedRelease {
                 attempted,
                 previous,
@@ -68,6 +278,98 @@ impl Display for AnalyzerError {
             } => {
                 format!("Thread 'T{thread_id}' tried to release the non-acquired lock 'L{lock_id}' in row {row}")
             }
+            AnalyzerError::LockHeldAtEnd {
+                lock_id,
+                owner,
+                row,
+            } => {
+                format!("Lock 'L{lock_id}' is still held by thread 'T{owner}' at the end of the trace. Lock was acquired in row {row}")
+            }
+            AnalyzerError::JoinedThreadHoldsLock {
+                row,
+                thread_id,
+                child_id,
+                lock_id,
+                acquired,
+            } => {
+                format!("Thread 'T{thread_id}' joined thread 'T{child_id}' in row {row} which still holds lock 'L{lock_id}'. Lock was acquired in row {acquired}")
+            }
+            AnalyzerError::ForkedItself { row, thread_id } => {
+                format!("Thread 'T{thread_id}' tried to fork itself in row {row}")
+            }
+            AnalyzerError::RepeatedFork {
+                attempted,
+                previous,
+                thread_id,
+                child_id,
+            } => {
+                format!("Thread 'T{thread_id}' tried to fork the already forked thread 'T{child_id}' in row {attempted}. Previous fork occurred in row {previous}")
+            }
+            AnalyzerError::ForkedStartedThread {
+                row,
+                thread_id,
+                child_id,
+                first_event,
+            } => {
+                format!("Thread 'T{thread_id}' tried to fork the already started thread 'T{child_id}' in row {row}. First event of the thread occurred in row {first_event}")
+            }
+            AnalyzerError::JoinedItself { row, thread_id } => {
+                format!("Thread 'T{thread_id}' tried to join itself in row {row}")
+            }
+            AnalyzerError::RepeatedJoin {
+                attempted,
+                previous,
+                thread_id,
+                child_id,
+            } => {
+                format!("Thread 'T{thread_id}' tried to join the already joined thread 'T{child_id}' in row {attempted}. Previous join occurred in row {previous}")
+            }
+            AnalyzerError::JoinedNonForkedThread {
+                row,
+                thread_id,
+                child_id,
+            } => {
+                format!("Thread 'T{thread_id}' tried to join the non-forked thread 'T{child_id}' in row {row}")
+            }
+            AnalyzerError::EventAfterJoin {
+                row,
+                thread_id,
+                joined,
+            } => {
+                format!("Thread 'T{thread_id}' performed an event in row {row} after it was joined in row {joined}")
+            }
+            AnalyzerError::UnmatchedEnd { row, thread_id } => {
+                format!("Thread 'T{thread_id}' tried to end an atomic block in row {row} without beginning it")
+            }
+            AnalyzerError::UnclosedBegin { row, thread_id } => {
+                format!(
+                    "Thread 'T{thread_id}' began an atomic block in row {row} which is never ended"
+                )
+            }
+            AnalyzerError::AcquiredWithoutRequest {
+                row,
+                lock_id,
+                thread_id,
+            } => {
+                format!("Thread 'T{thread_id}' tried to acquire the lock 'L{lock_id}' in row {row} without requesting it first")
+            }
+            AnalyzerError::DanglingRequest {
+                row,
+                lock_id,
+                thread_id,
+            } => {
+                format!("Thread 'T{thread_id}' requested the lock 'L{lock_id}' in row {row} without acquiring it afterwards")
+            }
+            AnalyzerError::ReadFromUnwrittenMemory {
+                row,
+                thread_id,
+                location,
+            } => {
+                format!("Thread 'T{thread_id}' tried to read from the unwritten memory location 'V{location}' in row {row}")
+            }
+            AnalyzerError::OutputError { path, error } => {
+                format!("Analyzer could not write the output '{path}': {error}")
+            }
             AnalyzerError::IOError(error) => {
                 format!(
                     "Analyzer encountered an error while performing I/O: {}",
@@ -86,6 +388,42 @@ impl Display for AnalyzerError {
             AnalyzerError::UnsupportedFileExtension => {
                 "Provided file extension is not supported".to_string()
             }
+            AnalyzerError::InvalidLocationMapping { row } => {
+                format!("Location map contains an invalid mapping in row {row}, expected 'loc|file|line'")
+            }
+            AnalyzerError::UnencodableValue {
+                row: Some(row),
+                field,
+                value,
+            } => {
+                format!("The {field} {value} of the event in row {row} exceeds the range of the RapidBin format")
+            }
+            AnalyzerError::UnencodableValue {
+                row: None,
+                field,
+                value,
+            } => {
+                format!("The number of {field} {value} exceeds the range of the RapidBin header")
+            }
+            AnalyzerError::HeaderMismatch {
+                field,
+                declared,
+                observed,
+            } => {
+                format!(
+                    "RapidBin header declares {declared} {field} but the trace contains {observed}"
+                )
+            }
+            AnalyzerError::TruncatedHeader => "RapidBin trace ends within its header".to_string(),
+            AnalyzerError::TrailingBytes { count } => {
+                format!("RapidBin trace ends with {count} bytes which don't form a complete event")
+            }
+            AnalyzerError::UnknownOperation { row, code } => {
+                format!("Event in row {row} has the unknown operation code {code}")
+            }
+            AnalyzerError::MalformedEvent { row, error } => {
+                format!("Event in row {row} could not be read: {error}")
+            }
         };
 
         write!(f, "{}", description)
@@ -38,6 +38,12 @@ pub enum Token {
     Release,
     #[token("join")]
     Join,
+    #[token("begin")]
+    Begin,
+    #[token("end")]
+    End,
+    #[token("branch")]
+    Branch,
     #[regex("[0-9]+", |lex| lex.slice().parse().ok())]
     LineNumber(i64),
 }
@@ -81,6 +87,7 @@ mod tests {
     }
 
     #[test]
+    #[allow(clippy::match_like_matches_macro)]
     fn fail_when_lexing_invalid_chars() -> Result<(), AnalyzerError> {
         // arrange
         let input = read_to_string("test/unsupported_character.std")?;
@@ -0,0 +1,15 @@
+pub mod analysis;
+pub mod analyzer;
+pub mod arguments;
+pub mod error;
+mod lexer;
+mod normalizer;
+pub mod parser;
+pub mod reader;
+pub mod sarif;
+pub mod writer;
+
+pub use error::AnalyzerError;
+pub use parser::{Event, Operand, Operation};
+pub use reader::{TraceFormat, TraceHeader, TraceReader};
+pub use writer::TraceWriter;
@@ -1,30 +1,148 @@
-use arguments::Arguments;
 use clap::Parser;
-use log::{error, info};
-
-mod analyzer;
-mod arguments;
-mod error;
-mod lexer;
-mod normalizer;
-mod parser;
+use log::{error, info, warn};
+use std::process;
+use trace_analyzer::analysis::{DeadlockKind, Report};
+use trace_analyzer::analyzer;
+use trace_analyzer::arguments::{Arguments, Cli, Command, ConvertArguments, OutputFormat};
+use trace_analyzer::sarif;
 
 fn main() {
     env_logger::init();
-    let arguments = Arguments::parse();
-
-    match analyzer::analyze_trace(&arguments) {
-        Ok(_) => info!("Analyzer could not find a violation"),
-        Err(errors) => {
-            error!(
-                "Analyzer found {} errors in the analyzed trace",
-                errors.len()
+    let cli = Cli::parse();
+
+    let arguments = match (cli.command, cli.arguments) {
+        (Some(Command::Convert(arguments)), _) => convert(&arguments),
+        (None, Some(arguments)) => arguments,
+        (None, None) => {
+            unreachable!("clap requires the arguments of the analysis without a subcommand")
+        }
+    };
+
+    let report = analyzer::analyze(&arguments);
+
+    match arguments.format {
+        OutputFormat::Text => log_report(&arguments, &report),
+        OutputFormat::Json => println!("{}", report.to_json()),
+        OutputFormat::Sarif => match sarif::to_sarif(&arguments, &report) {
+            Ok(log) => println!("{}", log),
+            Err(err) => {
+                error!("{}", err);
+                process::exit(analyzer::EXIT_INPUT_ERROR);
+            }
+        },
+    }
+
+    process::exit(analyzer::exit_code(&report, &arguments.fail_on));
+}
+
+/// Converts a trace and exits
+///
+/// # Arguments
+///
+/// * `arguments`: the command line arguments of the conversion
+///
+/// returns: ! the process exits with `EXIT_SUCCESS`, `EXIT_INPUT_ERROR` or `EXIT_OUTPUT_ERROR`
+///
+fn convert(arguments: &ConvertArguments) -> ! {
+    match analyzer::convert(arguments) {
+        Ok(metadata) => {
+            info!(
+                "Converted {} events of {} threads to '{}'",
+                metadata.events, metadata.threads, arguments.output
             );
+            process::exit(analyzer::EXIT_SUCCESS);
+        }
+        Err(err) if err.is_output_error() => {
+            error!("{}", err);
+            process::exit(analyzer::EXIT_OUTPUT_ERROR);
+        }
+        Err(err) => {
+            error!("{}", err);
+            process::exit(analyzer::EXIT_INPUT_ERROR);
+        }
+    }
+}
+
+/// Logs the results of the analysis
+///
+/// # Arguments
+///
+/// * `arguments`: the command line arguments
+/// * `report`: the results of the analysis
+///
+/// returns: () unit
+///
+fn log_report(arguments: &Arguments, report: &Report) {
+    if report.violations.is_empty() {
+        info!("Analyzer could not find a violation");
+    } else {
+        error!(
+            "Analyzer found {} errors in the analyzed trace",
+            report.violations.len()
+        );
+
+        if arguments.verbose {
+            for error in &report.violations {
+                error!("{}", error);
+            }
+        }
+    }
+
+    if arguments.lock_dependencies || arguments.spd || arguments.goodlock {
+        let predictable = report
+            .deadlocks
+            .iter()
+            .filter(|deadlock| deadlock.kind == DeadlockKind::Predictable)
+            .count();
+
+        warn!(
+            "Analyzer found {} potential deadlocks in the analyzed trace, {} of them are predictable",
+            report.deadlocks.len(),
+            predictable
+        );
+
+        if arguments.verbose {
+            for deadlock in &report.deadlocks {
+                warn!("{}", deadlock);
+            }
+        }
+    }
+
+    if arguments.races.is_some() {
+        warn!(
+            "Analyzer found {} data races in the analyzed trace",
+            report.races.len()
+        );
+
+        if arguments.verbose {
+            for race in &report.races {
+                warn!("{}", race);
+            }
+        }
+    }
+
+    if arguments.lockset {
+        warn!(
+            "Analyzer found {} memory locations with an empty lockset",
+            report.lockset_violations.len()
+        );
+
+        if arguments.verbose {
+            for violation in &report.lockset_violations {
+                warn!("{}", violation);
+            }
+        }
+    }
+
+    if arguments.atomicity {
+        warn!(
+            "Analyzer found {} non-serializable atomic blocks in the analyzed trace",
+            report.atomicity_violations.len()
+        );
 
-            if &arguments.verbose == &true {
-                for error in errors {
-                    error!("{}", error);
-                }
+        if arguments.verbose {
+            for violation in &report.atomicity_violations {
+                warn!("{}", violation);
             }
         }
     }
@@ -7,9 +7,22 @@ use crate::lexer::Token;
 ///
 /// * `tokens`: a vector of lexed tokens
 ///
-/// returns: Vec<Token, Global> a vector containing the transformed tokens
+/// returns: Vec<Token, Global> a vector containing the transformed tokens, the unchanged tokens if the event is
+/// truncated so that the parser reports it as malformed
 ///
 pub fn normalize_tokens(tokens: Vec<Token>) -> Vec<Token> {
+    try_normalize_tokens(&tokens).unwrap_or(tokens)
+}
+
+/// Transforms the lexed tokens of an event, see `normalize_tokens`
+///
+/// # Arguments
+///
+/// * `tokens`: a slice of lexed tokens
+///
+/// returns: Option<Vec<Token, Global>> a vector containing the transformed tokens, None if an operand is missing
+///
+fn try_normalize_tokens(tokens: &[Token]) -> Option<Vec<Token>> {
     let mut normalized_tokens = Vec::new();
     let mut token_iterator = tokens.iter();
 
@@ -21,7 +34,7 @@ pub fn normalize_tokens(tokens: Vec<Token>) -> Vec<Token> {
 
                 // skip 2 tokens
                 token_iterator.next();
-                let token = token_iterator.next().unwrap();
+                let token = token_iterator.next()?;
 
                 if let Token::LineNumber(loc) = token {
                     normalized_tokens.push(Token::MemoryLocation(loc.to_owned()));
@@ -33,7 +46,7 @@ pub fn normalize_tokens(tokens: Vec<Token>) -> Vec<Token> {
 
                 // skip 2 tokens
                 token_iterator.next();
-                let token = token_iterator.next().unwrap();
+                let token = token_iterator.next()?;
 
                 if let Token::LineNumber(loc) = token {
                     normalized_tokens.push(Token::MemoryLocation(loc.to_owned()));
@@ -45,7 +58,7 @@ pub fn normalize_tokens(tokens: Vec<Token>) -> Vec<Token> {
 
                 // skip 2 tokens
                 token_iterator.next();
-                let token = token_iterator.next().unwrap();
+                let token = token_iterator.next()?;
 
                 if let Token::LineNumber(loc) = token {
                     normalized_tokens.push(Token::ThreadIdentifier(loc.to_owned()));
@@ -57,7 +70,7 @@ pub fn normalize_tokens(tokens: Vec<Token>) -> Vec<Token> {
 
                 // skip 2 tokens
                 token_iterator.next();
-                let token = token_iterator.next().unwrap();
+                let token = token_iterator.next()?;
 
                 if let Token::LineNumber(loc) = token {
                     normalized_tokens.push(Token::ThreadIdentifier(loc.to_owned()));
@@ -69,7 +82,7 @@ pub fn normalize_tokens(tokens: Vec<Token>) -> Vec<Token> {
 
                 // skip 2 tokens
                 token_iterator.next();
-                let token = token_iterator.next().unwrap();
+                let token = token_iterator.next()?;
 
                 if let Token::LineNumber(loc) = token {
                     normalized_tokens.push(Token::LockIdentifier(loc.to_owned()));
@@ -81,7 +94,7 @@ pub fn normalize_tokens(tokens: Vec<Token>) -> Vec<Token> {
 
                 // skip 2 tokens
                 token_iterator.next();
-                let token = token_iterator.next().unwrap();
+                let token = token_iterator.next()?;
 
                 if let Token::LineNumber(loc) = token {
                     normalized_tokens.push(Token::LockIdentifier(loc.to_owned()));
@@ -93,7 +106,7 @@ pub fn normalize_tokens(tokens: Vec<Token>) -> Vec<Token> {
 
                 // skip 2 tokens
                 token_iterator.next();
-                let token = token_iterator.next().unwrap();
+                let token = token_iterator.next()?;
 
                 if let Token::LineNumber(loc) = token {
                     normalized_tokens.push(Token::LockIdentifier(loc.to_owned()));
@@ -103,5 +116,5 @@ pub fn normalize_tokens(tokens: Vec<Token>) -> Vec<Token> {
         }
     }
 
-    normalized_tokens
+    Some(normalized_tokens)
 }
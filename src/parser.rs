@@ -1,6 +1,7 @@
 use crate::error::AnalyzerError;
 use crate::lexer::Token;
 use peg::parser;
+use serde::Serialize;
 use std::fmt::{Display, Formatter};
 
 parser!(
@@ -11,6 +12,9 @@ parser!(
             = [ThreadIdentifier(thread_identifier)] [Pipe] operation:operation() [LeftParenthesis] operand:operand() [RightParenthesis] [Pipe] [LineNumber(loc)] {
                 Event { thread_identifier, operation, operand, loc }
             }
+            / [ThreadIdentifier(thread_identifier)] [Pipe] operation:annotation() ([LeftParenthesis] [RightParenthesis])? [Pipe] [LineNumber(loc)] {
+                Event { thread_identifier, operation, operand: Operand::None, loc }
+            }
 
         rule operation() -> Operation
             = [Read] { Operation::Read }
@@ -21,6 +25,11 @@ parser!(
             / [Fork] { Operation::Fork }
             / [Join] { Operation::Join }
 
+        rule annotation() -> Operation
+            = [Begin] { Operation::Begin }
+            / [End] { Operation::End }
+            / [Branch] { Operation::Branch }
+
         rule operand() -> Operand
             = [MemoryLocation(memory_location)] { Operand::MemoryLocation(memory_location) }
             / [LockIdentifier(lock_identifier)] { Operand::LockIdentifier(lock_identifier) }
@@ -50,7 +59,8 @@ impl Display for Event {
     }
 }
 
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
 pub enum Operation {
     Read,
     Write,
@@ -80,6 +90,22 @@ impl Operation {
             _ => None,
         }
     }
+
+    /// Returns the code of the operation in RapidBin format, the inverse of `Operation::new`
+    pub fn code(&self) -> i64 {
+        match self {
+            Operation::Acquire => 0,
+            Operation::Release => 1,
+            Operation::Read => 2,
+            Operation::Write => 3,
+            Operation::Fork => 4,
+            Operation::Join => 5,
+            Operation::Begin => 6,
+            Operation::End => 7,
+            Operation::Request => 8,
+            Operation::Branch => 9,
+        }
+    }
 }
 
 impl Display for Operation {
@@ -194,4 +220,25 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn succeed_when_parsing_annotation_without_operand() -> Result<(), AnalyzerError> {
+        // arrange
+        let input = read_to_string("test/begin_annotation.std")?;
+        let tokens = tokenize_source(input, true)?;
+
+        // act
+        let actual_event = parse_event(tokens)?;
+        let expected_event = Event {
+            thread_identifier: 1,
+            operation: Operation::Begin,
+            operand: Operand::None,
+            loc: 3,
+        };
+
+        // assert
+        assert_eq!(actual_event, expected_event);
+
+        Ok(())
+    }
 }
@@ -0,0 +1,688 @@
+use crate::analysis::TraceMetadata;
+use crate::error::AnalyzerError;
+use crate::lexer::tokenize_source;
+use crate::parser::{parse_event, Event, Operand, Operation};
+use clap::ValueEnum;
+use log::{debug, info};
+use serde::Serialize;
+use std::fs::File;
+use std::io;
+use std::io::{BufRead, BufReader, ErrorKind, Lines, Read};
+use std::path::Path;
+
+const NUM_THREADS_MASK: i16 = 0x7FFF;
+const NUM_LOCKS_MASK: i32 = 0x7FFFFFFF;
+const NUM_VARS_MASK: i32 = 0x7FFFFFFF;
+const NUM_EVENTS_MASK: i64 = 0x7FFFFFFFFFFFFFFF;
+
+pub(crate) const NUM_THREAD_BITS: i16 = 10;
+pub(crate) const THREAD_BITS_OFFSET: i16 = 0;
+pub(crate) const NUM_OPERATION_BITS: i16 = 4;
+pub(crate) const OPERATION_BITS_OFFSET: i16 = NUM_THREAD_BITS;
+pub(crate) const NUM_OPERAND_BITS: i16 = 34;
+pub(crate) const OPERAND_BITS_OFFSET: i16 = NUM_THREAD_BITS + NUM_OPERATION_BITS;
+pub(crate) const NUM_LOCATION_BITS: i16 = 15;
+pub(crate) const LOCATION_BITS_OFFSET: i16 =
+    NUM_THREAD_BITS + NUM_OPERATION_BITS + NUM_OPERAND_BITS;
+
+const THREAD_MASK: i64 = ((1 << NUM_THREAD_BITS) - 1) << THREAD_BITS_OFFSET;
+const OPERATION_MASK: i64 = ((1 << NUM_OPERATION_BITS) - 1) << OPERATION_BITS_OFFSET;
+const OPERAND_MASK: i64 = ((1 << NUM_OPERAND_BITS) - 1) << OPERAND_BITS_OFFSET;
+const LOCATION_MASK: i64 = ((1 << NUM_LOCATION_BITS) - 1) << LOCATION_BITS_OFFSET;
+
+/// The supported trace formats
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TraceFormat {
+    /// Human-readable text format, one event per row
+    Std,
+    /// Binary format of RAPID
+    #[value(name = "rapidbin", alias = "data")]
+    #[serde(rename = "rapidbin")]
+    RapidBin,
+}
+
+impl TraceFormat {
+    /// Determines the format of a trace by the extension of its path
+    ///
+    /// # Arguments
+    ///
+    /// * `path`: the path to the trace file
+    ///
+    /// returns: Option<TraceFormat> the format if the extension is supported, None otherwise
+    ///
+    pub fn from_extension<P: AsRef<Path>>(path: P) -> Option<Self> {
+        match path.as_ref().extension().and_then(|ext| ext.to_str()) {
+            Some("std") => Some(TraceFormat::Std),
+            Some("data") => Some(TraceFormat::RapidBin),
+            _ => None,
+        }
+    }
+
+    /// Detects the format of a trace by its first bytes without consuming them
+    ///
+    /// # Arguments
+    ///
+    /// * `trace_reader`: the reader positioned at the start of the trace
+    ///
+    /// returns: Result<Option<TraceFormat>, AnalyzerError> the detected format, None if the trace is empty
+    ///
+    pub fn sniff<R: BufRead + ?Sized>(trace_reader: &mut R) -> Result<Option<Self>, AnalyzerError> {
+        let bytes = trace_reader.fill_buf()?;
+
+        if bytes.is_empty() {
+            return Ok(None);
+        }
+
+        // text never contains zero bytes, while the header of a RapidBin trace starts with the high bytes of its counts
+        if bytes.contains(&0) {
+            Ok(Some(TraceFormat::RapidBin))
+        } else {
+            Ok(Some(TraceFormat::Std))
+        }
+    }
+}
+
+/// The header of a RapidBin trace
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct TraceHeader {
+    pub num_threads: i16,
+    pub num_locks: i32,
+    pub num_variables: i32,
+    pub num_events: i64,
+}
+
+impl TraceHeader {
+    /// Cross-checks the header against the events which were actually read
+    ///
+    /// The number of events has to match the number of records exactly, including records which could not be decoded.
+    /// RAPID indexes threads, locks and variables by their identifiers and may reserve identifiers which never occur,
+    /// so the trace must only not exceed these counts.
+    ///
+    /// # Arguments
+    ///
+    /// * `metadata`: the counts of the streamed trace
+    /// * `records`: the number of complete records which were read
+    ///
+    /// returns: Vec<AnalyzerError, Global> a mismatch for every count which contradicts the header
+    ///
+    pub fn validate(&self, metadata: &TraceMetadata, records: usize) -> Vec<AnalyzerError> {
+        let counts = [
+            ("events", self.num_events, records as i64),
+            (
+                "threads",
+                self.num_threads as i64,
+                metadata.max_thread_id.map_or(0, |id| id + 1),
+            ),
+            (
+                "locks",
+                self.num_locks as i64,
+                metadata.max_lock_id.map_or(0, |id| id + 1),
+            ),
+            (
+                "variables",
+                self.num_variables as i64,
+                metadata.max_variable_id.map_or(0, |id| id + 1),
+            ),
+        ];
+
+        counts
+            .into_iter()
+            .filter(|(field, declared, observed)| match *field {
+                "events" => declared != observed,
+                _ => observed > declared,
+            })
+            .map(
+                |(field, declared, observed)| AnalyzerError::HeaderMismatch {
+                    field,
+                    declared,
+                    observed,
+                },
+            )
+            .collect()
+    }
+}
+
+/// The path which denotes the standard input
+pub const STDIN_PATH: &str = "-";
+
+enum Source {
+    Std(Lines<Box<dyn BufRead>>),
+    RapidBin(Box<dyn BufRead>),
+}
+
+/// Streams the events of a trace in either STD or RapidBin format
+pub struct TraceReader {
+    source: Source,
+    format: TraceFormat,
+    normalize: bool,
+    header: Option<TraceHeader>,
+    strict: bool,
+    // the row of the last read event or record
+    row: usize,
+    // the number of complete records of a RapidBin trace, whether they could be decoded or not
+    records: usize,
+    // set after an error which prevents reading the remaining trace
+    exhausted: bool,
+}
+
+impl TraceReader {
+    /// Opens a trace file, the format is detected by its content (or its extension if it is empty)
+    ///
+    /// # Arguments
+    ///
+    /// * `path`: the path to the trace file, `-` for the standard input
+    /// * `normalize`: if the events of a STD trace need to be normalized first
+    ///
+    /// returns: Result<TraceReader, AnalyzerError> a reader yielding the events of the trace, an error if the file could not be opened
+    ///
+    pub fn open<P: AsRef<Path>>(path: P, normalize: bool) -> Result<Self, AnalyzerError> {
+        Self::open_with(path, None, normalize)
+    }
+
+    /// Opens a trace file in the given format regardless of its extension and content
+    ///
+    /// # Arguments
+    ///
+    /// * `path`: the path to the trace file, `-` for the standard input
+    /// * `format`: the format of the trace
+    /// * `normalize`: if the events of a STD trace need to be normalized first
+    ///
+    /// returns: Result<TraceReader, AnalyzerError> a reader yielding the events of the trace, an error if the file could not be opened
+    ///
+    pub fn open_as<P: AsRef<Path>>(
+        path: P,
+        format: TraceFormat,
+        normalize: bool,
+    ) -> Result<Self, AnalyzerError> {
+        Self::open_with(path, Some(format), normalize)
+    }
+
+    /// Opens a trace file in the given format, or detects the format if none is given
+    ///
+    /// # Arguments
+    ///
+    /// * `path`: the path to the trace file, `-` for the standard input
+    /// * `format`: the format of the trace, None to detect it by the content of the trace
+    /// * `normalize`: if the events of a STD trace need to be normalized first
+    ///
+    /// returns: Result<TraceReader, AnalyzerError> a reader yielding the events of the trace, an error if the file could not be opened
+    ///
+    pub fn open_with<P: AsRef<Path>>(
+        path: P,
+        format: Option<TraceFormat>,
+        normalize: bool,
+    ) -> Result<Self, AnalyzerError> {
+        let path = path.as_ref();
+
+        // stream content of file to avoid OOM
+        let mut trace_reader: Box<dyn BufRead> = if path == Path::new(STDIN_PATH) {
+            Box::new(io::stdin().lock())
+        } else {
+            Box::new(BufReader::new(File::open(path)?))
+        };
+
+        let format = match format {
+            Some(format) => format,
+            None => TraceFormat::sniff(&mut trace_reader)?
+                .or_else(|| TraceFormat::from_extension(path))
+                .unwrap_or(TraceFormat::Std),
+        };
+
+        Self::from_reader(trace_reader, format, normalize)
+    }
+
+    /// Reads a trace from an arbitrary source, e.g. a pipe
+    ///
+    /// # Arguments
+    ///
+    /// * `trace_reader`: the source of the trace
+    /// * `format`: the format of the trace
+    /// * `normalize`: if the events of a STD trace need to be normalized first
+    ///
+    /// returns: Result<TraceReader, AnalyzerError> a reader yielding the events of the trace, an error if the header of a
+    /// RapidBin trace could not be read
+    ///
+    pub fn from_reader(
+        mut trace_reader: Box<dyn BufRead>,
+        format: TraceFormat,
+        normalize: bool,
+    ) -> Result<Self, AnalyzerError> {
+        let (source, header) = match format {
+            TraceFormat::Std => (Source::Std(trace_reader.lines()), None),
+            TraceFormat::RapidBin => {
+                let header = parse_trace_header(&mut trace_reader)?;
+                (Source::RapidBin(trace_reader), Some(header))
+            }
+        };
+
+        Ok(Self {
+            source,
+            format,
+            normalize,
+            header,
+            strict: false,
+            row: 0,
+            records: 0,
+            exhausted: false,
+        })
+    }
+
+    /// Sets if the reader stops at the first malformed event, otherwise it reports the event and continues if the
+    /// remaining trace is still readable
+    ///
+    /// # Arguments
+    ///
+    /// * `strict`: if reading aborts at the first error
+    ///
+    /// returns: TraceReader the configured reader
+    ///
+    pub fn strict(mut self, strict: bool) -> Self {
+        self.strict = strict;
+        self
+    }
+
+    /// Returns the format of the trace
+    pub fn format(&self) -> TraceFormat {
+        self.format
+    }
+
+    /// Returns the header of the trace, only present for traces in RapidBin format
+    pub fn header(&self) -> Option<&TraceHeader> {
+        self.header.as_ref()
+    }
+
+    /// Returns the number of complete records read so far, only counted for traces in RapidBin format
+    pub fn records(&self) -> usize {
+        self.records
+    }
+}
+
+impl Iterator for TraceReader {
+    type Item = Result<Event, AnalyzerError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.exhausted {
+            return None;
+        }
+
+        self.row += 1;
+
+        let item = match &mut self.source {
+            Source::Std(lines) => match lines.next()? {
+                Ok(line) => tokenize_source(line, self.normalize)
+                    .and_then(parse_event)
+                    .map_err(|err| AnalyzerError::MalformedEvent {
+                        row: self.row,
+                        error: Box::new(err),
+                    }),
+                Err(err) => Err(AnalyzerError::from(err)),
+            },
+            Source::RapidBin(trace_reader) => {
+                let mut event_buffer = [0u8; 8];
+
+                match read_record(trace_reader, &mut event_buffer) {
+                    Ok(0) => return None,
+                    Ok(8) => {
+                        self.records += 1;
+                        try_parse_event(self.row, event_buffer)
+                    }
+                    Ok(count) => Err(AnalyzerError::TrailingBytes { count }),
+                    Err(err) => Err(AnalyzerError::from(err)),
+                }
+            }
+        };
+
+        // malformed lines and unknown operations are confined to their row, the following rows can still be read
+        if let Err(err) = &item {
+            self.exhausted = self.strict
+                || !matches!(
+                    err,
+                    AnalyzerError::MalformedEvent { .. } | AnalyzerError::UnknownOperation { .. }
+                );
+        }
+
+        Some(item)
+    }
+}
+
+/// Tries to parse the header of a RapidBin file which contains information about the amount of threads, locks, variables and events of a trace
+///
+/// # Arguments
+///
+/// * `trace_reader`: the reader containing the contents of RapidBin file
+///
+/// returns: Result<TraceHeader, AnalyzerError> the parsed header, an error if the file is too short
+///
+fn parse_trace_header<R: Read>(trace_reader: &mut R) -> Result<TraceHeader, AnalyzerError> {
+    let mut short_buffer = [0u8; 2];
+    let mut integer_buffer = [0u8; 4];
+    let mut long_buffer = [0u8; 8];
+
+    let truncated = |err: io::Error| match err.kind() {
+        ErrorKind::UnexpectedEof => AnalyzerError::TruncatedHeader,
+        _ => AnalyzerError::from(err),
+    };
+
+    trace_reader
+        .read_exact(&mut short_buffer)
+        .map_err(truncated)?;
+    let num_threads = i16::from_be_bytes(short_buffer) & NUM_THREADS_MASK;
+
+    trace_reader
+        .read_exact(&mut integer_buffer)
+        .map_err(truncated)?;
+    let num_locks = i32::from_be_bytes(integer_buffer) & NUM_LOCKS_MASK;
+
+    trace_reader
+        .read_exact(&mut integer_buffer)
+        .map_err(truncated)?;
+    let num_variables = i32::from_be_bytes(integer_buffer) & NUM_VARS_MASK;
+
+    trace_reader
+        .read_exact(&mut long_buffer)
+        .map_err(truncated)?;
+    let num_events = i64::from_be_bytes(long_buffer) & NUM_EVENTS_MASK;
+
+    info!("NUM_THREADS: {}", num_threads);
+    info!("NUM_LOCKS: {}", num_locks);
+    info!("NUM_VARIABLES: {}", num_variables);
+    info!("NUM_EVENTS: {}", num_events);
+
+    Ok(TraceHeader {
+        num_threads,
+        num_locks,
+        num_variables,
+        num_events,
+    })
+}
+
+/// Reads the next record of a RapidBin trace
+///
+/// # Arguments
+///
+/// * `trace_reader`: the reader positioned at the start of a record
+/// * `event_buffer`: the buffer receiving the bytes of the record
+///
+/// returns: Result<usize, Error> the number of read bytes, less than the size of a record if the trace ends within it
+///
+fn read_record<R: Read>(trace_reader: &mut R, event_buffer: &mut [u8; 8]) -> io::Result<usize> {
+    let mut count = 0;
+
+    while count < event_buffer.len() {
+        match trace_reader.read(&mut event_buffer[count..]) {
+            Ok(0) => break,
+            Ok(read) => count += read,
+            Err(err) if err.kind() == ErrorKind::Interrupted => {}
+            Err(err) => return Err(err),
+        }
+    }
+
+    Ok(count)
+}
+
+/// Tries to parse an event in RapidBin format
+///
+/// # Arguments
+///
+/// * `row`: the row of the event, used to report an unknown operation
+/// * `event_buffer`: the buffer containing the bytes of a RapidBin event
+///
+/// returns: Result<Event, AnalyzerError> the parsed event, an error if its operation code is unknown
+///
+fn try_parse_event(row: usize, event_buffer: [u8; 8]) -> Result<Event, AnalyzerError> {
+    let raw_event = i64::from_be_bytes(event_buffer);
+
+    let thread_identifier = (raw_event & THREAD_MASK) >> THREAD_BITS_OFFSET;
+    let operation_id = (raw_event & OPERATION_MASK) >> OPERATION_BITS_OFFSET;
+    let operand_id = (raw_event & OPERAND_MASK) >> OPERAND_BITS_OFFSET;
+    let loc = (raw_event & LOCATION_MASK) >> LOCATION_BITS_OFFSET;
+
+    let operation = Operation::new(operation_id).ok_or(AnalyzerError::UnknownOperation {
+        row,
+        code: operation_id,
+    })?;
+    let operand = Operand::new(&operation, operand_id);
+
+    let event = Event {
+        thread_identifier,
+        operation,
+        operand,
+        loc,
+    };
+
+    debug!("{:?}", event);
+
+    Ok(event)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn succeed_when_reading_std_trace() -> Result<(), AnalyzerError> {
+        // arrange
+        let reader = TraceReader::open("test/repeated_lock_release.std", true)?;
+
+        // act
+        let events = reader.collect::<Result<Vec<_>, _>>()?;
+
+        // assert
+        assert_eq!(events.len(), 8);
+        assert_eq!(
+            events[6],
+            Event {
+                thread_identifier: 6,
+                operation: Operation::Release,
+                operand: Operand::LockIdentifier(9),
+                loc: 51,
+            }
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn succeed_when_serializing_format_as_cli_value() {
+        // act
+        let formats = [TraceFormat::Std, TraceFormat::RapidBin]
+            .map(|format| serde_json::to_value(format).unwrap());
+
+        // assert
+        assert_eq!(formats, ["std", "rapidbin"]);
+    }
+
+    #[test]
+    fn succeed_when_reading_rapid_trace() -> Result<(), AnalyzerError> {
+        // arrange
+        let reader = TraceReader::open("input/Account.data", false)?;
+
+        // act
+        let header = reader.header().cloned().unwrap();
+        let events = reader.collect::<Result<Vec<_>, _>>()?;
+
+        // assert
+        assert_eq!(header.num_threads, 6);
+        assert_eq!(header.num_events, 706);
+        assert_eq!(events.len(), 706);
+        assert_eq!(
+            events[0],
+            Event {
+                thread_identifier: 0,
+                operation: Operation::Begin,
+                operand: Operand::None,
+                loc: 0,
+            }
+        );
+        assert_eq!(
+            events[104],
+            Event {
+                thread_identifier: 0,
+                operation: Operation::Acquire,
+                operand: Operand::LockIdentifier(1),
+                loc: 104,
+            }
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn detect_counts_exceeding_header() -> Result<(), AnalyzerError> {
+        // arrange
+        let header = TraceHeader {
+            num_threads: 2,
+            num_locks: 3,
+            num_variables: 4,
+            num_events: 10,
+        };
+        let metadata = TraceMetadata {
+            format: Some(TraceFormat::RapidBin),
+            threads: 2,
+            max_thread_id: Some(2),
+            locks: 2,
+            max_lock_id: Some(2),
+            variables: 2,
+            max_variable_id: Some(4),
+            events: 10,
+        };
+
+        // act
+        let mismatches = header.validate(&metadata, 10);
+
+        // assert
+        assert_eq!(mismatches.len(), 2);
+        assert!(matches!(
+            mismatches[0],
+            AnalyzerError::HeaderMismatch {
+                field: "threads",
+                declared: 2,
+                observed: 3
+            }
+        ));
+        assert!(matches!(
+            mismatches[1],
+            AnalyzerError::HeaderMismatch {
+                field: "variables",
+                declared: 4,
+                observed: 5
+            }
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn fail_when_header_is_truncated() -> Result<(), AnalyzerError> {
+        // act
+        let error = TraceReader::open("test/truncated_header.data", false)
+            .err()
+            .unwrap();
+
+        // assert
+        assert!(matches!(error, AnalyzerError::TruncatedHeader));
+
+        Ok(())
+    }
+
+    #[test]
+    fn fail_when_trace_ends_within_event() -> Result<(), AnalyzerError> {
+        // arrange
+        let reader = TraceReader::open("test/trailing_bytes.data", false)?;
+
+        // act
+        let events = reader.collect::<Vec<_>>();
+
+        // assert
+        assert_eq!(events.len(), 4);
+        assert!(events[..3].iter().all(Result::is_ok));
+        assert!(matches!(
+            events[3],
+            Err(AnalyzerError::TrailingBytes { count: 5 })
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn succeed_when_skipping_unknown_operation() -> Result<(), AnalyzerError> {
+        // arrange
+        let reader = TraceReader::open("test/unknown_operation.data", false)?;
+
+        // act
+        let events = reader.collect::<Vec<_>>();
+
+        // assert
+        assert_eq!(events.len(), 5);
+        assert!(matches!(
+            events[2],
+            Err(AnalyzerError::UnknownOperation { row: 3, code: 15 })
+        ));
+        assert!(events[3..].iter().all(Result::is_ok));
+
+        Ok(())
+    }
+
+    #[test]
+    fn fail_when_reading_unknown_operation_strictly() -> Result<(), AnalyzerError> {
+        // arrange
+        let reader = TraceReader::open("test/unknown_operation.data", false)?.strict(true);
+
+        // act
+        let events = reader.collect::<Vec<_>>();
+
+        // assert
+        assert_eq!(events.len(), 3);
+        assert!(matches!(
+            events[2],
+            Err(AnalyzerError::UnknownOperation { row: 3, code: 15 })
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn succeed_when_detecting_format_regardless_of_extension() -> Result<(), AnalyzerError> {
+        // arrange
+        let path =
+            std::env::temp_dir().join(format!("trace_analyzer_{}.trace", std::process::id()));
+        std::fs::copy("input/Deadlock.data", &path)?;
+
+        // act
+        let binary = TraceReader::open(&path, false)?;
+        let text = TraceReader::open("Cargo.toml", false)?;
+        let header = binary.header().cloned();
+        let events = binary.collect::<Result<Vec<_>, _>>()?;
+
+        std::fs::remove_file(&path)?;
+
+        // assert
+        assert_eq!(text.format(), TraceFormat::Std);
+        assert_eq!(header.unwrap().num_events, 39);
+        assert_eq!(events.len(), 39);
+
+        Ok(())
+    }
+
+    #[test]
+    fn succeed_when_reading_trace_from_any_source() -> Result<(), AnalyzerError> {
+        // arrange
+        let trace = std::fs::read("test/deadlock.std")?;
+        let mut source: Box<dyn BufRead> = Box::new(std::io::Cursor::new(trace));
+
+        // act
+        let format = TraceFormat::sniff(&mut source)?;
+        let events = TraceReader::from_reader(source, TraceFormat::Std, true)?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        // assert
+        assert_eq!(format, Some(TraceFormat::Std));
+        assert_eq!(
+            events,
+            TraceReader::open("test/deadlock.std", true)?.collect::<Result<Vec<_>, _>>()?
+        );
+
+        Ok(())
+    }
+}
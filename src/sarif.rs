@@ -0,0 +1,338 @@
+use crate::analysis::{DeadlockKind, Report};
+use crate::arguments::Arguments;
+use crate::error::AnalyzerError;
+use crate::reader::{TraceReader, STDIN_PATH};
+use serde_json::{json, Value};
+use std::collections::{BTreeSet, HashMap};
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::path::Path;
+
+const SARIF_SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+const SARIF_VERSION: &str = "2.1.0";
+
+/// Maps the `loc` of events to the source code of the instrumented program
+#[derive(Debug, Default)]
+pub struct LocationMap {
+    locations: HashMap<i64, (String, usize)>,
+}
+
+impl LocationMap {
+    /// Reads a location map where every row has the form `loc|file|line`
+    ///
+    /// # Arguments
+    ///
+    /// * `path`: the path to the location map
+    ///
+    /// returns: Result<LocationMap, AnalyzerError> the mapping of all rows, an error if a row is malformed or the file could not be read
+    ///
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self, AnalyzerError> {
+        let reader = BufReader::new(File::open(path)?);
+        let mut locations = HashMap::new();
+
+        for (row, line) in (1..).zip(reader.lines()) {
+            let line = line?;
+
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            let mut fields = line.split('|').map(str::trim);
+            let mapping = match (fields.next(), fields.next(), fields.next(), fields.next()) {
+                (Some(loc), Some(file), Some(line), None) if !file.is_empty() => loc
+                    .parse::<i64>()
+                    .ok()
+                    .zip(line.parse::<usize>().ok().filter(|line| *line > 0))
+                    .map(|(loc, line)| (loc, (file.to_string(), line))),
+                _ => None,
+            };
+
+            let (loc, location) = mapping.ok_or(AnalyzerError::InvalidLocationMapping { row })?;
+            locations.insert(loc, location);
+        }
+
+        Ok(Self { locations })
+    }
+
+    /// Returns the file and line of a `loc`, None if it is not mapped
+    pub fn get(&self, loc: i64) -> Option<(&str, usize)> {
+        self.locations
+            .get(&loc)
+            .map(|(file, line)| (file.as_str(), *line))
+    }
+}
+
+// a finding of the analysis together with the rows of the involved events
+struct Finding {
+    rule: String,
+    level: &'static str,
+    message: String,
+    rows: Vec<usize>,
+    // the locations which are already known without consulting the trace
+    locs: Vec<Option<i64>>,
+}
+
+/// Converts a report into a SARIF 2.1 log
+///
+/// # Arguments
+///
+/// * `arguments`: the command line arguments, used to look up the `loc` of the involved events in the trace
+/// * `report`: the results of the analysis
+///
+/// returns: Result<String, AnalyzerError> the SARIF log, an error if the trace or the location map could not be read
+///
+pub fn to_sarif(arguments: &Arguments, report: &Report) -> Result<String, AnalyzerError> {
+    let findings = findings_of(report);
+    let location_map = match &arguments.location_map {
+        Some(path) => LocationMap::open(path)?,
+        None => LocationMap::default(),
+    };
+
+    let unresolved = findings
+        .iter()
+        .flat_map(|finding| finding.rows.iter().zip(&finding.locs))
+        .filter(|(_, loc)| loc.is_none())
+        .map(|(row, _)| *row)
+        .collect::<BTreeSet<_>>();
+
+    let locs_of_rows = if unresolved.is_empty() {
+        HashMap::new()
+    } else {
+        locs_of_rows(arguments, &unresolved)?
+    };
+
+    let mut rules = BTreeSet::new();
+    let results = findings
+        .iter()
+        .map(|finding| {
+            rules.insert(finding.rule.clone());
+
+            let locs = finding
+                .rows
+                .iter()
+                .zip(&finding.locs)
+                .map(|(row, loc)| loc.or_else(|| locs_of_rows.get(row).copied()))
+                .collect::<Vec<_>>();
+
+            let locations = locs
+                .iter()
+                .flatten()
+                .filter_map(|loc| location_map.get(*loc))
+                .map(|(file, line)| {
+                    json!({
+                        "physicalLocation": {
+                            "artifactLocation": { "uri": file },
+                            "region": { "startLine": line }
+                        }
+                    })
+                })
+                .collect::<Vec<_>>();
+
+            json!({
+                "ruleId": finding.rule,
+                "level": finding.level,
+                "message": { "text": finding.message },
+                "locations": locations,
+                "properties": { "rows": finding.rows, "locs": locs }
+            })
+        })
+        .collect::<Vec<_>>();
+
+    let rules = rules
+        .into_iter()
+        .map(|rule| json!({ "id": rule }))
+        .collect::<Vec<_>>();
+
+    let log = json!({
+        "$schema": SARIF_SCHEMA,
+        "version": SARIF_VERSION,
+        "runs": [{
+            "tool": {
+                "driver": {
+                    "name": env!("CARGO_PKG_NAME"),
+                    "version": env!("CARGO_PKG_VERSION"),
+                    "rules": rules
+                }
+            },
+            "results": results
+        }]
+    });
+
+    Ok(serde_json::to_string_pretty(&log).expect("a SARIF log only contains serializable values"))
+}
+
+/// Collects the violations and analysis results of a report as findings
+fn findings_of(report: &Report) -> Vec<Finding> {
+    let mut findings = Vec::new();
+
+    for violation in &report.violations {
+        let rule = match serde_json::to_value(violation) {
+            Ok(Value::Object(fields)) => fields["kind"].as_str().unwrap_or_default().to_string(),
+            _ => String::new(),
+        };
+        let rows = violation.row().into_iter().collect::<Vec<_>>();
+
+        findings.push(Finding {
+            rule,
+            level: "error",
+            message: violation.to_string(),
+            locs: vec![None; rows.len()],
+            rows,
+        });
+    }
+
+    for deadlock in &report.deadlocks {
+        let rows = deadlock
+            .acquisitions
+            .iter()
+            .map(|acquisition| acquisition.row)
+            .collect::<Vec<_>>();
+
+        let (rule, level) = match deadlock.kind {
+            DeadlockKind::Potential => ("PotentialDeadlock", "warning"),
+            DeadlockKind::Predictable => ("PredictableDeadlock", "error"),
+        };
+
+        findings.push(Finding {
+            rule: rule.to_string(),
+            level,
+            message: deadlock.to_string(),
+            locs: vec![None; rows.len()],
+            rows,
+        });
+    }
+
+    for race in &report.races {
+        findings.push(Finding {
+            rule: "DataRace".to_string(),
+            level: "warning",
+            message: race.to_string(),
+            rows: vec![race.later.row, race.earlier.row],
+            locs: vec![Some(race.later.loc), Some(race.earlier.loc)],
+        });
+    }
+
+    for violation in &report.lockset_violations {
+        findings.push(Finding {
+            rule: "LocksetViolation".to_string(),
+            level: "warning",
+            message: violation.to_string(),
+            rows: vec![violation.access.row],
+            locs: vec![Some(violation.access.loc)],
+        });
+    }
+
+    for violation in &report.atomicity_violations {
+        let rows = violation
+            .transactions
+            .iter()
+            .map(|transaction| transaction.begin)
+            .collect::<Vec<_>>();
+
+        findings.push(Finding {
+            rule: "AtomicityViolation".to_string(),
+            level: "warning",
+            message: violation.to_string(),
+            locs: vec![None; rows.len()],
+            rows,
+        });
+    }
+
+    findings
+}
+
+/// Reads the trace again to look up the `loc` of the events in some rows
+///
+/// # Arguments
+///
+/// * `arguments`: the command line arguments
+/// * `rows`: the rows whose `loc` is needed
+///
+/// returns: Result<HashMap<usize, i64, RandomState>, AnalyzerError> the `loc` of every row, an error if the trace could not be read
+///
+fn locs_of_rows(
+    arguments: &Arguments,
+    rows: &BTreeSet<usize>,
+) -> Result<HashMap<usize, i64>, AnalyzerError> {
+    let last = *rows.last().unwrap();
+    let mut locs = HashMap::new();
+
+    // the standard input was already consumed by the analysis
+    if arguments.input == STDIN_PATH {
+        return Ok(locs);
+    }
+
+    let trace_reader = TraceReader::open_with(
+        &arguments.input,
+        arguments.input_format,
+        arguments.normalize,
+    )?
+    .strict(arguments.strict);
+
+    for (row, event) in (1..).zip(trace_reader) {
+        if row > last {
+            break;
+        }
+
+        // unreadable events were never analyzed
+        let Ok(event) = event else {
+            continue;
+        };
+
+        if rows.contains(&row) {
+            locs.insert(row, event.loc);
+        }
+    }
+
+    Ok(locs)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analyzer::analyze;
+
+    #[test]
+    fn succeed_when_mapping_violations_to_source_locations() -> Result<(), AnalyzerError> {
+        // arrange
+        let mut arguments =
+            Arguments::new("test/repeated_lock_release.std", true, false, false, false);
+        arguments.location_map = Some("test/locations.map".to_string());
+        let report = analyze(&arguments);
+
+        // act
+        let log = serde_json::from_str::<Value>(&to_sarif(&arguments, &report)?).unwrap();
+
+        // assert
+        let result = &log["runs"][0]["results"][0];
+        assert_eq!(log["version"], "2.1.0");
+        assert_eq!(result["ruleId"], "RepeatedRelease");
+        assert_eq!(result["level"], "error");
+        assert_eq!(
+            result["locations"][0]["physicalLocation"]["artifactLocation"]["uri"],
+            "src/Main.java"
+        );
+        assert_eq!(
+            result["locations"][0]["physicalLocation"]["region"]["startLine"],
+            42
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn fail_when_location_map_is_malformed() -> Result<(), AnalyzerError> {
+        // act
+        let error = LocationMap::open("test/repeated_lock_release.std")
+            .err()
+            .unwrap();
+
+        // assert
+        assert!(matches!(
+            error,
+            AnalyzerError::InvalidLocationMapping { row: 1 }
+        ));
+
+        Ok(())
+    }
+}
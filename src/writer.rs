@@ -0,0 +1,231 @@
+use crate::analysis::TraceMetadata;
+use crate::error::AnalyzerError;
+use crate::parser::{Event, Operand, Operation};
+use crate::reader::{
+    TraceFormat, TraceHeader, LOCATION_BITS_OFFSET, NUM_LOCATION_BITS, NUM_OPERAND_BITS,
+    NUM_THREAD_BITS, OPERAND_BITS_OFFSET, OPERATION_BITS_OFFSET, THREAD_BITS_OFFSET,
+};
+use std::io::Write;
+
+/// Writes the events of a trace in either STD or RapidBin format
+pub struct TraceWriter<W: Write> {
+    sink: W,
+    format: TraceFormat,
+    row: usize,
+}
+
+impl<W: Write> TraceWriter<W> {
+    /// Creates a writer, a trace in RapidBin format starts with its header
+    ///
+    /// # Arguments
+    ///
+    /// * `sink`: where the trace is written to
+    /// * `format`: the format of the written trace
+    /// * `header`: the counts of the trace, only written in RapidBin format
+    ///
+    /// returns: Result<TraceWriter<W>, AnalyzerError> the writer, an error if the header could not be written
+    ///
+    pub fn new(
+        mut sink: W,
+        format: TraceFormat,
+        header: &TraceHeader,
+    ) -> Result<Self, AnalyzerError> {
+        if format == TraceFormat::RapidBin {
+            sink.write_all(&encode_header(header))?;
+        }
+
+        Ok(Self {
+            sink,
+            format,
+            row: 0,
+        })
+    }
+
+    /// Appends an event to the trace
+    ///
+    /// # Arguments
+    ///
+    /// * `event`: the event to write
+    ///
+    /// returns: Result<(), AnalyzerError> unit if the event was written, an error if it can't be encoded or written
+    ///
+    pub fn write_event(&mut self, event: &Event) -> Result<(), AnalyzerError> {
+        self.row += 1;
+
+        match self.format {
+            TraceFormat::Std => writeln!(self.sink, "{}", format_event(event))?,
+            TraceFormat::RapidBin => self.sink.write_all(&encode_event(self.row, event)?)?,
+        }
+
+        Ok(())
+    }
+
+    /// Flushes the trace and returns the underlying sink
+    pub fn finish(mut self) -> Result<W, AnalyzerError> {
+        self.sink.flush()?;
+
+        Ok(self.sink)
+    }
+}
+
+impl TryFrom<&TraceMetadata> for TraceHeader {
+    type Error = AnalyzerError;
+
+    fn try_from(metadata: &TraceMetadata) -> Result<Self, Self::Error> {
+        Ok(TraceHeader {
+            // RAPID indexes its threads, locks and variables by their identifiers
+            num_threads: header_value("threads", metadata.max_thread_id.map_or(0, |id| id + 1))?,
+            num_locks: header_value("locks", metadata.max_lock_id.map_or(0, |id| id + 1))?,
+            num_variables: header_value(
+                "variables",
+                metadata.max_variable_id.map_or(0, |id| id + 1),
+            )?,
+            num_events: header_value("events", metadata.events as i64)?,
+        })
+    }
+}
+
+/// Formats an event as a row of a STD trace
+///
+/// # Arguments
+///
+/// * `event`: the event to format
+///
+/// returns: String the row without a line break, annotations are written without an operand
+///
+pub fn format_event(event: &Event) -> String {
+    let operation = match event.operation {
+        Operation::Read => "r",
+        Operation::Write => "w",
+        Operation::Acquire => "acq",
+        Operation::Request => "req",
+        Operation::Release => "rel",
+        Operation::Fork => "fork",
+        Operation::Join => "join",
+        Operation::Begin => "begin",
+        Operation::End => "end",
+        Operation::Branch => "branch",
+    };
+
+    match event.operand {
+        Operand::None => format!("T{}|{}|{}", event.thread_identifier, operation, event.loc),
+        _ => format!(
+            "T{}|{}({})|{}",
+            event.thread_identifier, operation, event.operand, event.loc
+        ),
+    }
+}
+
+/// Encodes an event in RapidBin format, the inverse of `try_parse_event`
+///
+/// # Arguments
+///
+/// * `row`: the row of the event, used to report values which don't fit
+/// * `event`: the event to encode
+///
+/// returns: Result<[u8; 8], AnalyzerError> the bytes of the event, an error if a value exceeds its bits
+///
+fn encode_event(row: usize, event: &Event) -> Result<[u8; 8], AnalyzerError> {
+    let thread_identifier = event_value(row, "thread", event.thread_identifier, NUM_THREAD_BITS)?;
+    let operand_id = event_value(
+        row,
+        "operand",
+        event.operand.id().unwrap_or_default(),
+        NUM_OPERAND_BITS,
+    )?;
+    let loc = event_value(row, "loc", event.loc, NUM_LOCATION_BITS)?;
+
+    let raw_event = (thread_identifier << THREAD_BITS_OFFSET)
+        | (event.operation.code() << OPERATION_BITS_OFFSET)
+        | (operand_id << OPERAND_BITS_OFFSET)
+        | (loc << LOCATION_BITS_OFFSET);
+
+    Ok(raw_event.to_be_bytes())
+}
+
+/// Encodes the header of a RapidBin trace
+fn encode_header(header: &TraceHeader) -> Vec<u8> {
+    let mut bytes = Vec::with_capacity(18);
+
+    bytes.extend(header.num_threads.to_be_bytes());
+    bytes.extend(header.num_locks.to_be_bytes());
+    bytes.extend(header.num_variables.to_be_bytes());
+    bytes.extend(header.num_events.to_be_bytes());
+
+    bytes
+}
+
+/// Checks that a value of an event fits into its bits
+fn event_value(
+    row: usize,
+    field: &'static str,
+    value: i64,
+    bits: i16,
+) -> Result<i64, AnalyzerError> {
+    if (0..1 << bits).contains(&value) {
+        return Ok(value);
+    }
+
+    Err(AnalyzerError::UnencodableValue {
+        row: Some(row),
+        field,
+        value,
+    })
+}
+
+/// Checks that a count of the trace fits into its field of the header
+fn header_value<T: TryFrom<i64>>(field: &'static str, value: i64) -> Result<T, AnalyzerError> {
+    T::try_from(value).map_err(|_| AnalyzerError::UnencodableValue {
+        row: None,
+        field,
+        value,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::reader::TraceReader;
+
+    #[test]
+    fn succeed_when_formatting_events_as_std() -> Result<(), AnalyzerError> {
+        // arrange
+        let events = TraceReader::open("test/begin_annotation.std", false)?
+            .chain(TraceReader::open("test/valid_trace.std", true)?)
+            .collect::<Result<Vec<_>, _>>()?;
+
+        // act
+        let rows = events.iter().map(format_event).collect::<Vec<_>>();
+
+        // assert
+        assert_eq!(rows, vec!["T1|begin|3", "T6|w(V4294967298)|59"]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn fail_when_encoding_thread_out_of_range() -> Result<(), AnalyzerError> {
+        // arrange
+        let event = Event {
+            thread_identifier: 1 << NUM_THREAD_BITS,
+            operation: Operation::Begin,
+            operand: Operand::None,
+            loc: 0,
+        };
+
+        // act
+        let error = encode_event(3, &event).err().unwrap();
+
+        // assert
+        assert!(matches!(
+            error,
+            AnalyzerError::UnencodableValue {
+                row: Some(3),
+                field: "thread",
+                value: 1024
+            }
+        ));
+
+        Ok(())
+    }
+}